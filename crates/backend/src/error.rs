@@ -22,12 +22,40 @@ pub enum AppError {
     FileTooBig(u64),
     #[error("screen not found")]
     ScreenNotFound,
+    #[error("content not found")]
+    ContentNotFound,
     #[error("slide group not found")]
     SlideGroupNotFound,
     #[error("slide group is archived and can't be edited")]
     SlideGroupArchived,
+    #[error("slide group must be shown on at least one weekday")]
+    InvalidRecurrence,
+    #[error("emergency message must not be empty")]
+    EmptyEmergencyMessage,
+    #[error("timestamp must be in RFC 3339 format")]
+    InvalidTimestamp,
+    #[error("only images can be spanned across screens")]
+    SpanRequiresImage,
+    #[error("url must be a valid http or https url")]
+    InvalidUrl,
+    #[error("web pages and text must be created in the browser instead of being uploaded")]
+    ContentNotUploadable,
+    #[error("colors must be in the format #rrggbb")]
+    InvalidColor,
+    #[error("background must be an image")]
+    BackgroundRequiresImage,
+    #[error("file must be an image")]
+    ImageRequired,
+    #[error("file must be a PDF")]
+    PdfRequired,
+    #[error("PDF must have at most {0} pages")]
+    TooManyPages(u32),
+    #[error("videos using the {0} codec can't be played by the screens")]
+    UnsupportedVideoCodec(String),
     #[error("slide not found")]
     SlideNotFound,
+    #[error("slide duration must be positive")]
+    InvalidSlideDuration,
     #[error("slide is archived and can't be edited")]
     SlideArchived,
     #[error("database error: {0}")]
@@ -40,6 +68,12 @@ pub enum AppError {
     IoError(#[from] std::io::Error),
     #[error("internal error: {0}")]
     InternalError(&'static str),
+    #[error("failed to read media metadata: {0}")]
+    MediaProbeError(String),
+    #[error("failed to process media: {0}")]
+    MediaProcessingError(String),
+    #[error("feed could not be computed: {0}")]
+    FeedUnavailable(String),
 
     #[error("you do not have permission to login")]
     LoginUnauthorized,
@@ -63,14 +97,31 @@ impl AppError {
             AppError::Unauthorized => Status::Forbidden,
             AppError::FileTooBig(_) => Status::PayloadTooLarge,
             AppError::ScreenNotFound => Status::NotFound,
+            AppError::ContentNotFound => Status::NotFound,
             AppError::SlideGroupNotFound => Status::NotFound,
             AppError::SlideGroupArchived => Status::Forbidden,
+            AppError::InvalidRecurrence => Status::BadRequest,
+            AppError::EmptyEmergencyMessage => Status::BadRequest,
+            AppError::InvalidTimestamp => Status::BadRequest,
+            AppError::SpanRequiresImage => Status::BadRequest,
+            AppError::InvalidUrl => Status::BadRequest,
+            AppError::ContentNotUploadable => Status::BadRequest,
+            AppError::InvalidColor => Status::BadRequest,
+            AppError::BackgroundRequiresImage => Status::BadRequest,
+            AppError::ImageRequired => Status::BadRequest,
+            AppError::PdfRequired => Status::BadRequest,
+            AppError::TooManyPages(_) => Status::BadRequest,
+            AppError::UnsupportedVideoCodec(_) => Status::BadRequest,
+            AppError::InvalidSlideDuration => Status::BadRequest,
             AppError::SlideNotFound => Status::NotFound,
             AppError::SlideArchived => Status::Forbidden,
             AppError::DatabaseError(_) => Status::InternalServerError,
             AppError::S3Error(_) => Status::InternalServerError,
             AppError::IoError(_) => Status::InternalServerError,
             AppError::InternalError(_) => Status::InternalServerError,
+            AppError::MediaProbeError(_) => Status::UnprocessableEntity,
+            AppError::MediaProcessingError(_) => Status::UnprocessableEntity,
+            AppError::FeedUnavailable(_) => Status::InternalServerError,
             AppError::LoginUnauthorized => Status::Forbidden,
             AppError::OidcAuthenticationError(_) => Status::InternalServerError,
             AppError::StateSerializationError(_) => Status::InternalServerError,
@@ -0,0 +1,385 @@
+//! Caches the feed of every screen with clients, along with the current emergency message, so that
+//! they are computed once whenever they may have changed instead of once per connected client.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Europe::Stockholm;
+use common::dtos::{AppErrorDto, EmergencyMessageDto, FeedDto};
+use rocket::{
+    tokio::{
+        self, select,
+        sync::{broadcast::error::RecvError, watch, Notify},
+        time::{self, Duration},
+    },
+    Orbit, Rocket, Shutdown,
+};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use sea_orm_rocket::Database;
+
+use crate::{
+    error::AppError,
+    files::Files,
+    notifier::FeedNotifier,
+    pool::Db,
+    recurrence::Recurrence,
+    routes::{
+        emergency::active_emergency_message,
+        screen_feed::{compute_feed, feed_entry_duration, feed_ordering},
+    },
+};
+
+/// How often the feeds are recomputed even if no change has been signaled, so that weekly
+/// schedules of slide groups are picked up.
+const FALLBACK_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The feed of a screen, or why it couldn't be computed.
+pub type CachedFeed = Result<FeedDto, AppErrorDto>;
+
+#[derive(Clone)]
+pub struct FeedCache {
+    /// Feeds are `None` until they have been computed.
+    feeds: Arc<Mutex<HashMap<i32, watch::Sender<Option<CachedFeed>>>>>,
+    /// Is `None` until it has been looked up, and then the current emergency message if any.
+    alert: watch::Sender<Option<Option<EmergencyMessageDto>>>,
+    /// Wakes up the background task when a feed which hasn't been computed is subscribed to.
+    refresh: Arc<Notify>,
+}
+
+impl FeedCache {
+    pub fn new() -> Self {
+        Self {
+            feeds: Arc::default(),
+            alert: watch::channel(None).0,
+            refresh: Arc::default(),
+        }
+    }
+
+    /// Subscribe to the feed of a screen, which is `None` until it has been computed. Subscribers
+    /// are only notified when the version of the feed changes.
+    pub fn subscribe(&self, screen: i32) -> watch::Receiver<Option<CachedFeed>> {
+        let mut feeds = self.feeds.lock().expect("feed cache lock is poisoned");
+        let sender = feeds
+            .entry(screen)
+            .or_insert_with(|| watch::channel(None).0);
+        if sender.borrow().is_none() {
+            self.refresh.notify_one();
+        }
+        sender.subscribe()
+    }
+
+    /// Subscribe to the current emergency message.
+    pub fn subscribe_alert(&self) -> watch::Receiver<Option<Option<EmergencyMessageDto>>> {
+        if self.alert.borrow().is_none() {
+            self.refresh.notify_one();
+        }
+        self.alert.subscribe()
+    }
+
+    /// Get the feed of a screen, waiting for it to be computed if needed.
+    pub async fn get(&self, screen: i32) -> Result<CachedFeed, AppError> {
+        let mut receiver = self.subscribe(screen);
+        let feed = receiver
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| AppError::InternalError("feed cache has been shut down"))?
+            .clone();
+        Ok(feed.expect("feed has been computed"))
+    }
+
+    /// Screens whose feed has subscribers. The feeds of other screens are forgotten, so that they
+    /// are computed again once subscribed to.
+    fn watched_screens(&self) -> Vec<i32> {
+        let mut feeds = self.feeds.lock().expect("feed cache lock is poisoned");
+        feeds.retain(|_, sender| sender.receiver_count() > 0);
+        let mut screens: Vec<i32> = feeds.keys().copied().collect();
+        screens.sort();
+        screens
+    }
+
+    fn publish(&self, screen: i32, feed: CachedFeed) {
+        let feeds = self.feeds.lock().expect("feed cache lock is poisoned");
+        let Some(sender) = feeds.get(&screen) else {
+            return;
+        };
+        sender.send_if_modified(|current| {
+            // screens restart their slideshow when receiving a feed, so skip unchanged ones
+            let changed = match (current.as_ref(), &feed) {
+                (Some(Ok(current)), Ok(feed)) => current.version != feed.version,
+                (Some(Err(current)), Err(err)) => current != err,
+                _ => true,
+            };
+            if changed {
+                *current = Some(feed);
+            }
+            changed
+        });
+    }
+
+    fn publish_alert(&self, alert: Option<EmergencyMessageDto>) {
+        self.alert.send_if_modified(|current| {
+            let changed = current.as_ref() != Some(&alert);
+            if changed {
+                *current = Some(alert);
+            }
+            changed
+        });
+    }
+}
+
+impl Default for FeedCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Start recomputing the cached feeds in the background whenever they may have changed.
+pub async fn start(rocket: &Rocket<Orbit>) {
+    let db = Db::fetch(rocket)
+        .expect("Rocket is in orbit phase")
+        .conn
+        .clone();
+    let files = rocket.state::<Files>().expect("Files are managed").clone();
+    let notifier = rocket
+        .state::<FeedNotifier>()
+        .expect("Feed notifier is managed")
+        .clone();
+    let cache = rocket
+        .state::<FeedCache>()
+        .expect("Feed cache is managed")
+        .clone();
+
+    tokio::spawn(refresh_feeds(cache, db, files, notifier, rocket.shutdown()));
+}
+
+/// Recompute the emergency message and the watched feeds whenever a change is signaled, a slide
+/// group starts or ends, the emergency message expires or a new feed is subscribed to, and at
+/// least every [`FALLBACK_REFRESH_INTERVAL`].
+async fn refresh_feeds(
+    cache: FeedCache,
+    db: DatabaseConnection,
+    files: Files,
+    notifier: FeedNotifier,
+    mut shutdown: Shutdown,
+) {
+    let mut changes = notifier.subscribe();
+    let feed_entry_duration = feed_entry_duration();
+    let ordering = feed_ordering();
+
+    loop {
+        let now = Utc::now();
+        let mut next_refresh = now + FALLBACK_REFRESH_INTERVAL;
+
+        match active_emergency_message(&db, &files).await {
+            Ok(alert) => {
+                if let Some(expires_at) = alert.as_ref().and_then(|alert| alert.expires_at) {
+                    next_refresh = next_refresh.min(expires_at);
+                }
+                cache.publish_alert(alert);
+            }
+            Err(err) => {
+                error!("While refreshing the emergency message, encountered {err:?}: {err}")
+            }
+        }
+
+        for screen in cache.watched_screens() {
+            let feed = compute_feed(
+                screen,
+                &db,
+                &files,
+                feed_entry_duration,
+                ordering,
+                now,
+                None,
+            )
+            .await
+            .map_err(|err| {
+                if err.status().code >= 500 {
+                    // debug prints enum variant name, display shows thiserror message
+                    error!(
+                        "While refreshing the feed of screen {screen}, encountered {err:?}: {err}"
+                    );
+                }
+                AppErrorDto::from(err)
+            });
+            cache.publish(screen, feed);
+        }
+
+        match next_date_boundary(&db, now).await {
+            Ok(Some(boundary)) => next_refresh = next_refresh.min(boundary),
+            Ok(None) => {}
+            Err(err) => {
+                error!("While looking up when the feeds change, encountered {err:?}: {err}")
+            }
+        }
+
+        let sleep = (next_refresh - Utc::now()).to_std().unwrap_or_default();
+        select! {
+            _ = time::sleep(sleep) => {}
+            // lagging behind only means that several changes happened at once
+            change = changes.recv() => if let Err(RecvError::Closed) = change {
+                break;
+            },
+            _ = cache.refresh.notified() => {}
+            _ = &mut shutdown => break,
+        }
+    }
+}
+
+/// The next time after `now` that a slide group starts or ends, or one of their recurring time
+/// windows does, which changes the feeds.
+async fn next_date_boundary(
+    db: &DatabaseConnection,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, AppError> {
+    let mut boundary: Option<DateTime<Utc>> = None;
+    for column in [
+        entity::slide_group::Column::StartDate,
+        entity::slide_group::Column::EndDate,
+    ] {
+        let date: Option<chrono::NaiveDateTime> = entity::slide_group::Entity::find()
+            .select_only()
+            .column(column)
+            .filter(entity::slide_group::Column::ArchiveDate.is_null())
+            .filter(
+                Condition::all()
+                    .add(entity::slide_group::Column::Published.eq(true))
+                    .add(entity::slide_group::Column::Hidden.eq(false)),
+            )
+            .filter(column.gt(now))
+            .order_by_asc(column)
+            .into_tuple()
+            .one(db)
+            .await?;
+        if let Some(date) = date.map(|date| date.and_utc()) {
+            boundary = Some(boundary.map_or(date, |boundary| boundary.min(date)));
+        }
+    }
+
+    let recurrences: Vec<(Option<i32>, Option<NaiveTime>, Option<NaiveTime>)> =
+        entity::slide_group::Entity::find()
+            .select_only()
+            .columns([
+                entity::slide_group::Column::RecurrenceWeekdays,
+                entity::slide_group::Column::RecurrenceStartTime,
+                entity::slide_group::Column::RecurrenceEndTime,
+            ])
+            .filter(entity::slide_group::Column::ArchiveDate.is_null())
+            .filter(
+                Condition::all()
+                    .add(entity::slide_group::Column::Published.eq(true))
+                    .add(entity::slide_group::Column::Hidden.eq(false)),
+            )
+            .filter(entity::slide_group::Column::RecurrenceWeekdays.is_not_null())
+            .filter(
+                Condition::any()
+                    .add(entity::slide_group::Column::EndDate.is_null())
+                    .add(entity::slide_group::Column::EndDate.gt(now)),
+            )
+            .into_tuple()
+            .all(db)
+            .await?;
+    let recurrences = recurrences
+        .into_iter()
+        .filter_map(|(weekdays, start_time, end_time)| {
+            Recurrence::from_columns(weekdays, start_time, end_time)
+        });
+    if let Some(date) = next_recurrence_change(recurrences, now) {
+        boundary = Some(boundary.map_or(date, |boundary| boundary.min(date)));
+    }
+
+    Ok(boundary)
+}
+
+/// The next time after `now` that one of the recurring time windows starts or ends.
+fn next_recurrence_change(
+    recurrences: impl IntoIterator<Item = Recurrence>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let local_now = now.with_timezone(&Stockholm).naive_local();
+    recurrences
+        .into_iter()
+        .filter_map(|recurrence| recurrence.next_change_after(local_now))
+        // times skipped when switching to summer time don't exist, so they are never reached
+        .filter_map(|change| Stockholm.from_local_datetime(&change).earliest())
+        .map(|change| change.to_utc())
+        .filter(|change| *change > now)
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveTime, TimeZone, Utc};
+    use common::dtos::{AppErrorDto, FeedDto};
+
+    use super::{next_recurrence_change, FeedCache};
+    use crate::recurrence::Recurrence;
+
+    fn feed(version: &str) -> FeedDto {
+        FeedDto {
+            version: version.to_string(),
+            entries: vec![],
+            server_time: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn subscribers_are_notified_of_changes() {
+        let cache = FeedCache::new();
+        let mut receiver = cache.subscribe(1);
+        assert_eq!(*receiver.borrow_and_update(), None);
+
+        cache.publish(1, Ok(feed("a")));
+        assert!(receiver.has_changed().unwrap());
+        receiver.mark_unchanged();
+
+        // the server time isn't compared, as it is set when the feed is sent
+        cache.publish(1, Ok(feed("a")));
+        assert!(!receiver.has_changed().unwrap());
+
+        let error = AppErrorDto {
+            msg: "database error".to_string(),
+        };
+        cache.publish(1, Err(error.clone()));
+        assert_eq!(*receiver.borrow_and_update(), Some(Err(error)));
+    }
+
+    #[test]
+    fn unwatched_feeds_are_forgotten() {
+        let cache = FeedCache::new();
+        let first = cache.subscribe(1);
+        let second = cache.subscribe(2);
+        cache.publish(1, Ok(feed("a")));
+        assert_eq!(cache.watched_screens(), vec![1, 2]);
+
+        drop(first);
+        assert_eq!(cache.watched_screens(), vec![2]);
+        drop(second);
+        assert!(cache.watched_screens().is_empty());
+
+        // the feed is computed again once subscribed to
+        assert_eq!(*cache.subscribe(1).borrow(), None);
+    }
+
+    #[test]
+    fn recurrence_changes_in_swedish_time() {
+        let recurrence = Recurrence {
+            weekdays: 1 << 3, // Thursday
+            start_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+        };
+        // Thursday 2026-10-15 at noon in Stockholm, which is two hours ahead of UTC in October
+        let now = Utc.with_ymd_and_hms(2026, 10, 15, 10, 0, 0).unwrap();
+
+        assert_eq!(
+            next_recurrence_change([recurrence], now),
+            Some(Utc.with_ymd_and_hms(2026, 10, 15, 15, 0, 0).unwrap())
+        );
+        assert_eq!(next_recurrence_change([], now), None);
+    }
+}
@@ -3,6 +3,7 @@ use rocket::{
     data::Capped,
     fairing::{self, Fairing, Info, Kind},
     fs::TempFile,
+    http::ContentType,
     tokio::io::AsyncReadExt,
     Build, Rocket,
 };
@@ -14,7 +15,7 @@ use crate::error::AppError;
 
 pub struct FilesInitializer;
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 struct S3Config {
     url: String,
     bucket: String,
@@ -22,6 +23,12 @@ struct S3Config {
     use_mock: bool,
 }
 
+pub struct UploadedFile {
+    /// Object key in the S3 bucket.
+    pub key: String,
+}
+
+#[derive(Clone)]
 pub struct Files {
     s3_client: aws_sdk_s3::Client,
     s3_config: S3Config,
@@ -69,14 +76,20 @@ impl Fairing for FilesInitializer {
 }
 
 impl Files {
-    pub async fn upload_file(&self, file: &mut Capped<TempFile<'_>>) -> Result<String, AppError> {
-        if !file.is_complete() {
-            return Err(AppError::FileTooBig(file.len()));
-        }
-
-        let mut content = Vec::new();
-        file.open().await?.read_to_end(&mut content).await?;
+    pub async fn upload_file(
+        &self,
+        file: &mut Capped<TempFile<'_>>,
+    ) -> Result<UploadedFile, AppError> {
+        let content = read_file(file).await?;
+        self.upload(content, file.content_type()).await
+    }
 
+    /// Upload content which didn't come directly from the client, e.g. processed media.
+    pub async fn upload(
+        &self,
+        content: Vec<u8>,
+        content_type: Option<&ContentType>,
+    ) -> Result<UploadedFile, AppError> {
         let hash = {
             let mut hasher = Sha256::new();
             hasher.update(&content);
@@ -86,7 +99,7 @@ impl Files {
             })
         };
 
-        let key = if let Some(ext) = file.content_type().and_then(|ct| ct.extension()) {
+        let key = if let Some(ext) = content_type.and_then(|ct| ct.extension()) {
             hash + "." + ext.as_str()
         } else {
             hash
@@ -97,17 +110,41 @@ impl Files {
             .bucket(&self.s3_config.bucket)
             .key(&key)
             .body(ByteStream::from(content))
-            .set_content_type(
-                file.content_type()
-                    .map(|content_type| content_type.to_string()),
-            )
+            .set_content_type(content_type.map(|content_type| content_type.to_string()))
             .send()
             .await?;
 
-        Ok(key)
+        Ok(UploadedFile { key })
     }
 
     pub fn file_url(&self, key: &str) -> String {
         format!("{}/{}/{}", self.s3_config.url, self.s3_config.bucket, key)
     }
+
+    /// Get the url of content, given its type and `file_path`. Content of type url stores the url
+    /// of the page to show instead of an object key, and text may not have a background image.
+    pub fn content_url(
+        &self,
+        content_type: &entity::sea_orm_active_enums::ContentType,
+        file_path: &str,
+    ) -> String {
+        match content_type {
+            entity::sea_orm_active_enums::ContentType::Url => file_path.to_string(),
+            entity::sea_orm_active_enums::ContentType::Text if file_path.is_empty() => {
+                String::new()
+            }
+            _ => self.file_url(file_path),
+        }
+    }
+}
+
+/// Read an uploaded file, which must not have exceeded the size limit.
+pub async fn read_file(file: &Capped<TempFile<'_>>) -> Result<Vec<u8>, AppError> {
+    if !file.is_complete() {
+        return Err(AppError::FileTooBig(file.len()));
+    }
+
+    let mut content = Vec::new();
+    file.open().await?.read_to_end(&mut content).await?;
+    Ok(content)
 }
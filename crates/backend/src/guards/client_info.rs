@@ -0,0 +1,25 @@
+use std::convert::Infallible;
+
+use rocket::{
+    request::{FromRequest, Outcome},
+    Request,
+};
+
+/// Describes the client making the request, for keeping track of connected screens.
+pub struct ClientInfo {
+    /// Taken from `X-Real-IP` if set, otherwise the address of the connection.
+    pub remote_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ClientInfo {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(Self {
+            remote_address: req.client_ip().map(|ip| ip.to_string()),
+            user_agent: req.headers().get_one("User-Agent").map(str::to_string),
+        })
+    }
+}
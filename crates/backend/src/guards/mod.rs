@@ -1 +1,2 @@
+pub mod client_info;
 pub mod scheme;
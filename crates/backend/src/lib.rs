@@ -4,14 +4,17 @@ use std::{
 };
 
 use auth::oidc::OidcInitializer;
+use feed_cache::FeedCache;
 use files::FilesInitializer;
 use migration::MigratorTrait;
+use notifier::FeedNotifier;
 use pool::Db;
 use rocket::{
     fairing::{self, AdHoc},
     fs::{FileServer, NamedFile},
     Build, Rocket,
 };
+use screen_registry::ScreenRegistry;
 use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait};
 use sea_orm_rocket::Database;
 
@@ -22,11 +25,19 @@ extern crate rocket;
 
 mod auth;
 mod error;
+mod feed_cache;
 mod files;
 mod guards;
+mod media;
+mod notifier;
 mod pool;
+mod recurrence;
+mod rotation;
 mod routes;
 mod scheduler;
+mod screen_commands;
+mod screen_registry;
+mod span;
 #[cfg(test)]
 mod test_utils;
 
@@ -102,21 +113,46 @@ pub(crate) fn rocket() -> Rocket<Build> {
         .attach(HiveInitializer)
         .attach(OidcInitializer)
         .attach(Db::init())
+        .manage(FeedNotifier::new())
+        .manage(FeedCache::new())
+        .manage(ScreenRegistry::new())
         .attach(AdHoc::try_on_ignite("Migrations", run_migrations))
         .attach(AdHoc::try_on_ignite("Screens", setup_screens))
+        .attach(AdHoc::try_on_ignite(
+            "PDF config",
+            routes::content::check_pdf_config,
+        ))
         .attach(AdHoc::on_liftoff("Scheduled tasks", |rocket| {
             Box::pin(scheduler::start(rocket))
         }))
+        .attach(AdHoc::on_liftoff("Feed cache", |rocket| {
+            Box::pin(feed_cache::start(rocket))
+        }))
         .mount("/", FileServer::from("/www/static/").rank(11))
         .mount("/", routes![serve_file])
         .mount(
             "/api",
             routes![
                 routes::content::create_content,
+                routes::content::create_pdf_content,
+                routes::content::create_span_content,
+                routes::content::create_text_content,
+                routes::content::create_url_content,
+                routes::emergency::clear_emergency_message,
+                routes::emergency::get_emergency_message,
+                routes::emergency::raise_emergency_message,
                 routes::health::health,
                 routes::screen::create_screen,
+                routes::screen::get_fallback_content,
                 routes::screen::list_screens,
+                routes::screen::list_screen_statuses,
+                routes::screen::send_command_to_all_screens,
+                routes::screen::send_screen_command,
+                routes::screen::set_fallback_content,
+                routes::screen::update_screen,
                 routes::screen_feed::get_screen_feed,
+                routes::screen_feed::get_screen_feed_once,
+                routes::screen_feed::preview_feeds,
                 routes::slide::create_slide,
                 routes::slide::bulk_move_slides,
                 routes::slide::delete_slide,
@@ -125,6 +161,7 @@ pub(crate) fn rocket() -> Rocket<Build> {
                 routes::slide_group::get_slide_group,
                 routes::slide_group::list_slide_groups,
                 routes::slide_group::update_slide_group,
+                routes::time::server_time,
             ],
         )
         .register("/api", catchers![routes::auth::not_logged_in])
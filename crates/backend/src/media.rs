@@ -0,0 +1,534 @@
+//! Extracts metadata from uploaded media using `ffprobe`, and processes it using `ffmpeg`, which
+//! must both be available in `PATH`. PDFs are rasterized using `pdfinfo` and `pdftoppm` from
+//! poppler, which must be available too.
+
+use std::{
+    fs,
+    io::Write,
+    path::Path,
+    process::{Command, Output},
+};
+
+use rocket::tokio::task;
+use serde::Deserialize;
+use tempfile::NamedTempFile;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoMetadata {
+    pub codec: String,
+    pub duration_ms: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Part of an image, along with the size it's scaled to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crop {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub scaled_width: i32,
+    pub scaled_height: i32,
+}
+
+#[derive(Deserialize)]
+struct ProbeOutput {
+    streams: Vec<ProbeStream>,
+    format: ProbeFormat,
+}
+
+#[derive(Deserialize)]
+struct ProbeStream {
+    // only requested for videos
+    #[serde(default)]
+    codec_name: Option<String>,
+    width: i32,
+    height: i32,
+}
+
+#[derive(Deserialize)]
+struct ImageProbeOutput {
+    streams: Vec<ProbeStream>,
+}
+
+#[derive(Deserialize)]
+struct FrameProbeOutput {
+    frames: Vec<ProbeFrame>,
+}
+
+#[derive(Deserialize)]
+struct ProbeFrame {
+    #[serde(default)]
+    tags: ProbeFrameTags,
+}
+
+#[derive(Deserialize, Default)]
+struct ProbeFrameTags {
+    // EXIF orientation, which ffprobe outputs as a string
+    #[serde(rename = "Orientation")]
+    orientation: Option<String>,
+}
+
+/// Size that an image is scaled down to fit in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rendition {
+    pub max_width: i32,
+    pub max_height: i32,
+}
+
+#[derive(Deserialize)]
+struct ProbeFormat {
+    // ffprobe outputs numbers as strings
+    duration: String,
+}
+
+/// Probe the codec, duration and resolution of the first video stream in the given file.
+pub async fn probe_video(content: Vec<u8>) -> Result<VideoMetadata, AppError> {
+    let output = task::spawn_blocking(move || {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&content)?;
+        file.flush()?;
+
+        Command::new("ffprobe")
+            .args([
+                "-v",
+                "error",
+                "-select_streams",
+                "v:0",
+                "-show_entries",
+                "stream=codec_name,width,height:format=duration",
+                "-of",
+                "json",
+            ])
+            .arg(file.path())
+            .output()
+    })
+    .await
+    .map_err(|_| AppError::InternalError("media probe task panicked"))??;
+
+    if !output.status.success() {
+        return Err(AppError::MediaProbeError(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    parse_probe_output(&output.stdout).map_err(AppError::MediaProbeError)
+}
+
+/// Probe the resolution of the given image once it is upright, as `(width, height)`.
+pub async fn probe_image(content: Vec<u8>) -> Result<(i32, i32), AppError> {
+    let (orientation, output) = task::spawn_blocking(move || {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&content)?;
+        file.flush()?;
+
+        let orientation = probe_orientation(file.path())?;
+        let output = Command::new("ffprobe")
+            .args([
+                "-v",
+                "error",
+                "-select_streams",
+                "v:0",
+                "-show_entries",
+                "stream=width,height",
+                "-of",
+                "json",
+            ])
+            .arg(file.path())
+            .output()?;
+        std::io::Result::Ok((orientation, output))
+    })
+    .await
+    .map_err(|_| AppError::InternalError("media probe task panicked"))??;
+
+    if !output.status.success() {
+        return Err(AppError::MediaProbeError(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    let probe: ImageProbeOutput = serde_json::from_slice(&output.stdout)
+        .map_err(|err| AppError::MediaProbeError(err.to_string()))?;
+    probe
+        .streams
+        .first()
+        .map(|stream| match is_transposed(orientation) {
+            true => (stream.height, stream.width),
+            false => (stream.width, stream.height),
+        })
+        .ok_or_else(|| AppError::MediaProbeError("file is not an image".to_string()))
+}
+
+/// Cut the given crops out of an image, returning each of them as a PNG image. The crops are
+/// relative to the image once it is upright, as given by [`probe_image`].
+pub async fn crop_image(content: Vec<u8>, crops: Vec<Crop>) -> Result<Vec<Vec<u8>>, AppError> {
+    let outputs = task::spawn_blocking(move || {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&content)?;
+        file.flush()?;
+
+        let orientation = probe_orientation(file.path())?;
+        crops
+            .iter()
+            .map(|crop| {
+                let crop_filter = format!(
+                    "crop={}:{}:{}:{},scale={}:{}",
+                    crop.width, crop.height, crop.x, crop.y, crop.scaled_width, crop.scaled_height
+                );
+                let filter = match orientation_filter(orientation) {
+                    Some(orientation) => format!("{orientation},{crop_filter}"),
+                    None => crop_filter,
+                };
+                // orientation is applied by hand, as only some versions of ffmpeg apply it
+                Command::new("ffmpeg")
+                    .args(["-v", "error", "-noautorotate", "-i"])
+                    .arg(file.path())
+                    .args([
+                        "-vf",
+                        &filter,
+                        "-map_metadata",
+                        "-1",
+                        "-frames:v",
+                        "1",
+                        "-f",
+                        "image2pipe",
+                        "-c:v",
+                        "png",
+                        "pipe:1",
+                    ])
+                    .output()
+            })
+            .collect::<std::io::Result<Vec<Output>>>()
+    })
+    .await
+    .map_err(|_| AppError::InternalError("media processing task panicked"))??;
+
+    let mut images = Vec::with_capacity(outputs.len());
+    for output in outputs {
+        if !output.status.success() {
+            return Err(AppError::MediaProcessingError(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        images.push(output.stdout);
+    }
+
+    Ok(images)
+}
+
+/// Render an image at each of the given sizes, as JPEG if `jpeg` is set and as PNG otherwise. The
+/// image is rotated according to its EXIF orientation, and the metadata is stripped. Images are
+/// only scaled down, never up.
+pub async fn render_image(
+    content: Vec<u8>,
+    renditions: Vec<Rendition>,
+    jpeg: bool,
+) -> Result<Vec<Vec<u8>>, AppError> {
+    let outputs = task::spawn_blocking(move || {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&content)?;
+        file.flush()?;
+
+        let orientation = probe_orientation(file.path())?;
+
+        let mut outputs = Vec::with_capacity(renditions.len());
+        for rendition in renditions {
+            let scale = scale_filter(rendition);
+            let filter = match orientation_filter(orientation) {
+                Some(orientation) => format!("{orientation},{scale}"),
+                None => scale,
+            };
+            outputs.push(
+                // orientation is applied by hand, as only some versions of ffmpeg apply it
+                Command::new("ffmpeg")
+                    .args(["-v", "error", "-noautorotate", "-i"])
+                    .arg(file.path())
+                    .args(["-vf", &filter, "-map_metadata", "-1", "-frames:v", "1"])
+                    .args(["-f", "image2pipe", "-c:v"])
+                    .args(if jpeg {
+                        ["mjpeg", "-q:v", "3"].as_slice()
+                    } else {
+                        ["png"].as_slice()
+                    })
+                    .arg("pipe:1")
+                    .output()?,
+            );
+        }
+        std::io::Result::Ok(outputs)
+    })
+    .await
+    .map_err(|_| AppError::InternalError("media processing task panicked"))??;
+
+    let mut images = Vec::with_capacity(outputs.len());
+    for output in outputs {
+        if !output.status.success() {
+            return Err(AppError::MediaProcessingError(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        images.push(output.stdout);
+    }
+
+    Ok(images)
+}
+
+/// Extract a representative frame of a video to show instead of it, as a JPEG image scaled down to
+/// fit in `rendition`.
+pub async fn extract_poster(content: Vec<u8>, rendition: Rendition) -> Result<Vec<u8>, AppError> {
+    let output = task::spawn_blocking(move || {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&content)?;
+        file.flush()?;
+
+        // the thumbnail filter avoids picking a black frame at the start of the video
+        Command::new("ffmpeg")
+            .args(["-v", "error", "-i"])
+            .arg(file.path())
+            .args([
+                "-vf",
+                &format!("thumbnail,{}", scale_filter(rendition)),
+                "-map_metadata",
+                "-1",
+                "-frames:v",
+                "1",
+                "-f",
+                "image2pipe",
+                "-c:v",
+                "mjpeg",
+                "-q:v",
+                "3",
+                "pipe:1",
+            ])
+            .output()
+    })
+    .await
+    .map_err(|_| AppError::InternalError("media processing task panicked"))??;
+
+    if !output.status.success() {
+        return Err(AppError::MediaProcessingError(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Get the ffmpeg filter which scales media down to fit in `rendition`, keeping its aspect ratio.
+fn scale_filter(rendition: Rendition) -> String {
+    format!(
+        "scale=w='min({},iw)':h='min({},ih)':force_original_aspect_ratio=decrease",
+        rendition.max_width, rendition.max_height
+    )
+}
+
+/// Probe the EXIF orientation of the image in the given file, which is `None` if it has none or
+/// it can't be read.
+fn probe_orientation(path: &Path) -> std::io::Result<Option<u8>> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-read_intervals",
+            "%+#1",
+            "-show_entries",
+            "frame_tags=Orientation",
+            "-of",
+            "json",
+        ])
+        .arg(path)
+        .output()?;
+    Ok(parse_orientation(&output.stdout))
+}
+
+/// Get the EXIF orientation of the first frame from the output of `ffprobe`, if it has one.
+fn parse_orientation(output: &[u8]) -> Option<u8> {
+    let probe: FrameProbeOutput = serde_json::from_slice(output).ok()?;
+    probe
+        .frames
+        .first()?
+        .tags
+        .orientation
+        .as_ref()?
+        .parse()
+        .ok()
+}
+
+/// Get the ffmpeg filter which turns an image with the given EXIF orientation upright.
+fn orientation_filter(orientation: Option<u8>) -> Option<&'static str> {
+    match orientation? {
+        2 => Some("hflip"),
+        3 => Some("hflip,vflip"),
+        4 => Some("vflip"),
+        5 => Some("transpose=cclock_flip"),
+        6 => Some("transpose=clock"),
+        7 => Some("transpose=clock_flip"),
+        8 => Some("transpose=cclock"),
+        // 1 is already upright
+        _ => None,
+    }
+}
+
+/// Whether an image with the given EXIF orientation has its width and height swapped once upright.
+fn is_transposed(orientation: Option<u8>) -> bool {
+    matches!(orientation, Some(5..=8))
+}
+
+/// Render every page of a PDF as a PNG image whose longer side is `resolution` pixels. Fails with
+/// [`AppError::TooManyPages`] if it has more than `max_pages` pages.
+pub async fn rasterize_pdf(
+    content: Vec<u8>,
+    max_pages: u32,
+    resolution: u32,
+) -> Result<Vec<Vec<u8>>, AppError> {
+    let (file, info) = task::spawn_blocking(move || {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&content)?;
+        file.flush()?;
+
+        let info = Command::new("pdfinfo").arg(file.path()).output()?;
+        std::io::Result::Ok((file, info))
+    })
+    .await
+    .map_err(|_| AppError::InternalError("media probe task panicked"))??;
+    if !info.status.success() {
+        return Err(AppError::MediaProbeError(
+            String::from_utf8_lossy(&info.stderr).trim().to_string(),
+        ));
+    }
+    let pages = parse_page_count(&String::from_utf8_lossy(&info.stdout))
+        .ok_or_else(|| AppError::MediaProbeError("PDF has no page count".to_string()))?;
+    if pages > max_pages {
+        return Err(AppError::TooManyPages(max_pages));
+    }
+
+    let (output, images) = task::spawn_blocking(move || {
+        let dir = tempfile::tempdir()?;
+        let output = Command::new("pdftoppm")
+            .args(["-png", "-scale-to", &resolution.to_string()])
+            .args(["-l", &max_pages.to_string()])
+            .arg(file.path())
+            .arg(dir.path().join("page"))
+            .output()?;
+
+        // pages are numbered with the same amount of digits, so they sort in order
+        let mut paths = fs::read_dir(dir.path())?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        paths.sort();
+        let images = paths
+            .iter()
+            .map(fs::read)
+            .collect::<std::io::Result<Vec<_>>>()?;
+        std::io::Result::Ok((output, images))
+    })
+    .await
+    .map_err(|_| AppError::InternalError("media processing task panicked"))??;
+
+    if !output.status.success() {
+        return Err(AppError::MediaProcessingError(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(images)
+}
+
+/// Get the number of pages from the output of `pdfinfo`.
+fn parse_page_count(output: &str) -> Option<u32> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("Pages:"))
+        .and_then(|pages| pages.trim().parse().ok())
+}
+
+fn parse_probe_output(output: &[u8]) -> Result<VideoMetadata, String> {
+    let probe: ProbeOutput = serde_json::from_slice(output).map_err(|err| err.to_string())?;
+
+    let stream = probe
+        .streams
+        .first()
+        .ok_or_else(|| "file has no video stream".to_string())?;
+    let codec = stream
+        .codec_name
+        .clone()
+        .ok_or_else(|| "video stream has no codec".to_string())?;
+    let duration_secs = probe
+        .format
+        .duration
+        .parse::<f64>()
+        .map_err(|err| err.to_string())?;
+
+    Ok(VideoMetadata {
+        codec,
+        duration_ms: (duration_secs * 1000.0).round() as i32,
+        width: stream.width,
+        height: stream.height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_transposed, orientation_filter, parse_orientation, parse_page_count, parse_probe_output,
+        VideoMetadata,
+    };
+
+    #[test]
+    fn parse_video_metadata() {
+        let output = br#"{
+            "programs": [],
+            "streams": [{ "codec_name": "h264", "width": 1920, "height": 1080 }],
+            "format": { "duration": "12.345678" }
+        }"#;
+
+        assert_eq!(
+            parse_probe_output(output).unwrap(),
+            VideoMetadata {
+                codec: "h264".to_string(),
+                duration_ms: 12346,
+                width: 1920,
+                height: 1080,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_without_video_stream() {
+        let output = br#"{ "streams": [], "format": { "duration": "3.0" } }"#;
+
+        assert!(parse_probe_output(output).is_err());
+    }
+
+    #[test]
+    fn parse_image_orientation() {
+        let output = br#"{ "frames": [{ "tags": { "Orientation": "6" } }] }"#;
+        assert_eq!(parse_orientation(output), Some(6));
+        assert_eq!(orientation_filter(Some(6)), Some("transpose=clock"));
+
+        let output = br#"{ "frames": [{}] }"#;
+        assert_eq!(parse_orientation(output), None);
+        assert_eq!(orientation_filter(None), None);
+        assert_eq!(orientation_filter(Some(1)), None);
+    }
+
+    #[test]
+    fn transposed_orientations() {
+        assert!(is_transposed(Some(6)));
+        assert!(is_transposed(Some(8)));
+        assert!(!is_transposed(Some(3)));
+        assert!(!is_transposed(None));
+    }
+
+    #[test]
+    fn parse_pdf_info() {
+        let output = "Title:           Pub crawl\nProducer:        LibreOffice\nPages:           3\nEncrypted:       no\n";
+
+        assert_eq!(parse_page_count(output), Some(3));
+        assert_eq!(parse_page_count("Title: Pages: 3\n"), None);
+    }
+}
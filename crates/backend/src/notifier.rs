@@ -0,0 +1,60 @@
+//! Broadcasts changes which may affect the screen feeds, so that connected screens can be sent an
+//! updated feed right away instead of waiting for the next periodic refresh.
+
+use rocket::tokio::sync::broadcast::{self, Receiver, Sender};
+
+/// Notifications are only used as a wake-up signal, so a small buffer is enough. Receivers which
+/// lag behind will simply recompute their feed once.
+const CHANNEL_CAPACITY: usize = 16;
+
+#[derive(Clone)]
+pub struct FeedNotifier {
+    sender: Sender<()>,
+}
+
+impl FeedNotifier {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Signal to every subscriber that slide groups, slides or content have changed.
+    pub fn notify(&self) {
+        // sending only fails if there are no subscribers, i.e. no screens connected
+        let _ = self.sender.send(());
+    }
+
+    pub fn subscribe(&self) -> Receiver<()> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for FeedNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::tokio::sync::broadcast::error::TryRecvError;
+
+    use super::FeedNotifier;
+
+    #[test]
+    fn notify_subscribers() {
+        let notifier = FeedNotifier::new();
+
+        // notifying without subscribers is not an error
+        notifier.notify();
+
+        let mut first = notifier.subscribe();
+        let mut second = notifier.subscribe();
+        assert_eq!(first.try_recv(), Err(TryRecvError::Empty));
+
+        notifier.notify();
+        assert_eq!(first.try_recv(), Ok(()));
+        assert_eq!(second.try_recv(), Ok(()));
+        assert_eq!(first.try_recv(), Err(TryRecvError::Empty));
+    }
+}
@@ -0,0 +1,220 @@
+//! Weekly recurring time windows, which limit when a slide group is shown.
+
+use chrono::{Datelike, Days, NaiveDateTime, NaiveTime, Weekday};
+use common::dtos::RecurrenceDto;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Recurrence as stored in the `slide_group` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recurrence {
+    /// Bitmask where Monday is the least significant bit.
+    pub weekdays: i32,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
+
+impl Recurrence {
+    /// Build from the nullable columns of a slide group, which are either all set or all `None`.
+    pub fn from_columns(
+        weekdays: Option<i32>,
+        start_time: Option<NaiveTime>,
+        end_time: Option<NaiveTime>,
+    ) -> Option<Self> {
+        Some(Self {
+            weekdays: weekdays?,
+            start_time: start_time?,
+            end_time: end_time?,
+        })
+    }
+
+    /// Returns `None` if no weekdays are included, as the slide group would then never be shown.
+    pub fn from_dto(dto: &RecurrenceDto) -> Option<Self> {
+        if dto.weekdays.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            weekdays: dto
+                .weekdays
+                .iter()
+                .fold(0, |mask, day| mask | 1 << day.num_days_from_monday()),
+            start_time: dto.start_time,
+            end_time: dto.end_time,
+        })
+    }
+
+    pub fn to_dto(self) -> RecurrenceDto {
+        RecurrenceDto {
+            weekdays: WEEKDAYS
+                .into_iter()
+                .filter(|day| self.includes(*day))
+                .collect(),
+            start_time: self.start_time,
+            end_time: self.end_time,
+        }
+    }
+
+    fn includes(&self, day: Weekday) -> bool {
+        self.weekdays & (1 << day.num_days_from_monday()) != 0
+    }
+
+    /// Checks if the given local (Swedish) time is within one of the recurring time windows.
+    pub fn is_active_at(&self, at: NaiveDateTime) -> bool {
+        let time = at.time();
+
+        if self.start_time < self.end_time {
+            self.includes(at.weekday()) && self.start_time <= time && time < self.end_time
+        } else {
+            // the window wraps around midnight, so it might have started the day before
+            let started_today = self.includes(at.weekday()) && self.start_time <= time;
+            let started_yesterday = at
+                .checked_sub_days(Days::new(1))
+                .is_some_and(|yesterday| self.includes(yesterday.weekday()))
+                && time < self.end_time;
+            started_today || started_yesterday
+        }
+    }
+
+    /// The next local (Swedish) time after `at` when one of the recurring time windows starts or
+    /// ends.
+    pub fn next_change_after(&self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        // windows which started yesterday may end today, and a week later every window repeats
+        let yesterday = at.date().checked_sub_days(Days::new(1))?;
+        let mut next: Option<NaiveDateTime> = None;
+        for day in yesterday.iter_days().take(9) {
+            if !self.includes(day.weekday()) {
+                continue;
+            }
+            let start = day.and_time(self.start_time);
+            let end = if self.start_time < self.end_time {
+                day.and_time(self.end_time)
+            } else {
+                day.checked_add_days(Days::new(1))?.and_time(self.end_time)
+            };
+            for change in [start, end] {
+                if change > at && next.is_none_or(|next| change < next) {
+                    next = Some(change);
+                }
+            }
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+    use common::dtos::RecurrenceDto;
+
+    use super::Recurrence;
+
+    fn time(hour: u32, min: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, min, 0).unwrap()
+    }
+
+    /// 2026-10-15 is a Thursday.
+    fn thursday_at(hour: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 15)
+            .unwrap()
+            .and_time(time(hour, min))
+    }
+
+    #[test]
+    fn dto_round_trip() {
+        let dto = RecurrenceDto {
+            weekdays: vec![Weekday::Mon, Weekday::Thu, Weekday::Sun],
+            start_time: time(17, 0),
+            end_time: time(23, 0),
+        };
+
+        let recurrence = Recurrence::from_dto(&dto).unwrap();
+        assert_eq!(recurrence.weekdays, 0b1001001);
+        assert_eq!(recurrence.to_dto(), dto);
+    }
+
+    #[test]
+    fn empty_weekdays_are_invalid() {
+        let dto = RecurrenceDto {
+            weekdays: vec![],
+            start_time: time(17, 0),
+            end_time: time(23, 0),
+        };
+
+        assert_eq!(Recurrence::from_dto(&dto), None);
+    }
+
+    #[test]
+    fn active_within_window() {
+        let recurrence = Recurrence {
+            weekdays: 1 << 3, // Thursday
+            start_time: time(17, 0),
+            end_time: time(23, 0),
+        };
+
+        assert!(!recurrence.is_active_at(thursday_at(16, 59)));
+        assert!(recurrence.is_active_at(thursday_at(17, 0)));
+        assert!(recurrence.is_active_at(thursday_at(22, 59)));
+        assert!(!recurrence.is_active_at(thursday_at(23, 0)));
+        // Wednesday
+        assert!(!recurrence.is_active_at(thursday_at(18, 0) - chrono::Days::new(1)));
+    }
+
+    #[test]
+    fn active_past_midnight() {
+        let recurrence = Recurrence {
+            weekdays: 1 << 3, // Thursday
+            start_time: time(22, 0),
+            end_time: time(2, 0),
+        };
+
+        assert!(recurrence.is_active_at(thursday_at(23, 0)));
+        // early on Thursday belongs to Wednesday's window
+        assert!(!recurrence.is_active_at(thursday_at(1, 0)));
+        // Friday
+        assert!(recurrence.is_active_at(thursday_at(1, 0) + chrono::Days::new(1)));
+        assert!(!recurrence.is_active_at(thursday_at(2, 0) + chrono::Days::new(1)));
+    }
+
+    #[test]
+    fn next_change() {
+        let recurrence = Recurrence {
+            weekdays: 1 << 3, // Thursday
+            start_time: time(17, 0),
+            end_time: time(23, 0),
+        };
+
+        assert_eq!(
+            recurrence.next_change_after(thursday_at(12, 0)),
+            Some(thursday_at(17, 0))
+        );
+        assert_eq!(
+            recurrence.next_change_after(thursday_at(17, 0)),
+            Some(thursday_at(23, 0))
+        );
+        // next week
+        assert_eq!(
+            recurrence.next_change_after(thursday_at(23, 0)),
+            Some(thursday_at(17, 0) + chrono::Days::new(7))
+        );
+
+        // the window of Wednesday ends on Thursday
+        let recurrence = Recurrence {
+            weekdays: 1 << 2, // Wednesday
+            start_time: time(22, 0),
+            end_time: time(2, 0),
+        };
+        assert_eq!(
+            recurrence.next_change_after(thursday_at(1, 0)),
+            Some(thursday_at(2, 0))
+        );
+    }
+}
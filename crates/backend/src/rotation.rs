@@ -0,0 +1,183 @@
+//! Decides in which order the slides of the active slide groups are rotated on the screens.
+//!
+//! Every function here must be deterministic, as the feed of each screen is computed separately
+//! and the screens are only kept in sync by showing the same sequence of slides.
+
+use std::str::FromStr;
+
+use common::dtos::MAX_PRIORITY;
+use sha2::{Digest, Sha256};
+
+/// How the slides of the active slide groups are ordered within a rotation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RotationOrder {
+    /// All slides of a group are shown in a row, in order of the group ids.
+    #[default]
+    Grouped,
+    /// One slide of a group is shown at a time, spreading the slides of each group between the
+    /// other groups.
+    Interleaved,
+    /// Like [`RotationOrder::Grouped`], but the groups and their slides are shuffled, with a new
+    /// order every day.
+    Shuffled,
+}
+
+impl FromStr for RotationOrder {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "grouped" => Ok(RotationOrder::Grouped),
+            "interleaved" => Ok(RotationOrder::Interleaved),
+            "shuffled" => Ok(RotationOrder::Shuffled),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How many times the slides of a group with the given priority are shown per rotation.
+pub fn group_weight(priority: i32) -> usize {
+    priority.clamp(0, MAX_PRIORITY) as usize + 1
+}
+
+/// Build a rotation where each group's slides appear as many times as the group's weight, with the
+/// repetitions of a group spread out as evenly as possible between the other groups.
+///
+/// With [`RotationOrder::Interleaved`], groups take turns showing a single slide, going through
+/// their slides in order. Each group gets as many turns per round as its weight, regardless of how
+/// many slides it has, and there are as many rounds as the largest group has slides, so groups
+/// with fewer slides start over. [`RotationOrder::Shuffled`] doesn't affect this, as the groups
+/// must be shuffled beforehand, see [`shuffle_key`].
+pub fn weighted_rotation<T: Clone>(groups: Vec<(usize, Vec<T>)>, order: RotationOrder) -> Vec<T> {
+    let weights: Vec<usize> = groups.iter().map(|(weight, _)| *weight).collect();
+    let turns = smooth_round_robin(&weights);
+    if order != RotationOrder::Interleaved {
+        return turns
+            .into_iter()
+            .flat_map(|index| groups[index].1.iter().cloned())
+            .collect();
+    }
+
+    let rounds = groups
+        .iter()
+        .map(|(_, slides)| slides.len())
+        .max()
+        .unwrap_or_default();
+    let mut slides: Vec<_> = groups
+        .iter()
+        .map(|(_, slides)| slides.iter().cycle())
+        .collect();
+    (0..rounds)
+        .flat_map(|_| turns.iter())
+        .filter_map(|index| slides[*index].next().cloned())
+        .collect()
+}
+
+/// Pick the indices of the given weights, each as many times as its weight, spread out as evenly
+/// as possible.
+///
+/// Uses smooth weighted round-robin, with ties broken by the order of `weights`.
+fn smooth_round_robin(weights: &[usize]) -> Vec<usize> {
+    let total_weight: usize = weights.iter().sum();
+    let mut current_weights = vec![0; weights.len()];
+    let mut picks = Vec::with_capacity(total_weight);
+
+    for _ in 0..total_weight {
+        for (current, weight) in current_weights.iter_mut().zip(weights) {
+            *current += *weight as isize;
+        }
+
+        // `max_by_key` returns the last maximum, so reverse to prefer earlier groups on ties
+        let Some((index, _)) = current_weights
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, current)| **current)
+        else {
+            break;
+        };
+
+        current_weights[index] -= total_weight as isize;
+        picks.push(index);
+    }
+
+    picks
+}
+
+/// Key to sort slide groups or slides by to shuffle them, given their id and the day of the
+/// rotation as seed.
+///
+/// Each id is hashed on its own, so that the groups shown on several screens are in the same
+/// relative order on all of them, even if other groups are only shown on some.
+pub fn shuffle_key(seed: &str, id: i32) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(seed);
+    hasher.update([0]);
+    hasher.update(id.to_be_bytes());
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_weight, shuffle_key, weighted_rotation, RotationOrder};
+
+    #[test]
+    fn weights_from_priority() {
+        assert_eq!(group_weight(-3), 1);
+        assert_eq!(group_weight(0), 1);
+        assert_eq!(group_weight(2), 3);
+        assert_eq!(group_weight(1000), 10);
+    }
+
+    #[test]
+    fn equal_weights_keep_order() {
+        let rotation = weighted_rotation(
+            vec![(1, vec!["a1", "a2"]), (1, vec!["b1"])],
+            RotationOrder::Grouped,
+        );
+        assert_eq!(rotation, vec!["a1", "a2", "b1"]);
+    }
+
+    #[test]
+    fn higher_weights_are_spread_out() {
+        let rotation = weighted_rotation(
+            vec![(1, vec!["a"]), (3, vec!["b"]), (1, vec!["c"])],
+            RotationOrder::Grouped,
+        );
+        assert_eq!(rotation, vec!["b", "a", "b", "c", "b"]);
+    }
+
+    #[test]
+    fn empty_rotation() {
+        assert_eq!(
+            weighted_rotation::<i32>(vec![], RotationOrder::Interleaved),
+            Vec::<i32>::new()
+        );
+    }
+
+    #[test]
+    fn interleaved_groups_take_turns() {
+        let rotation = weighted_rotation(
+            vec![(1, vec!["a1", "a2", "a3"]), (1, vec!["b1"])],
+            RotationOrder::Interleaved,
+        );
+        assert_eq!(rotation, vec!["a1", "b1", "a2", "b1", "a3", "b1"]);
+
+        // turns depend on the weight of the groups only, not on their number of slides
+        let rotation = weighted_rotation(
+            vec![(2, vec!["a1", "a2"]), (1, vec!["b1", "b2", "b3", "b4"])],
+            RotationOrder::Interleaved,
+        );
+        assert_eq!(
+            rotation,
+            vec!["a1", "b1", "a2", "a1", "b2", "a2", "a1", "b3", "a2", "a1", "b4", "a2"]
+        );
+    }
+
+    #[test]
+    fn shuffle_depends_on_seed_and_id() {
+        assert_eq!(shuffle_key("2026-10-17", 1), shuffle_key("2026-10-17", 1));
+        assert_ne!(shuffle_key("2026-10-17", 1), shuffle_key("2026-10-17", 2));
+        assert_ne!(shuffle_key("2026-10-17", 1), shuffle_key("2026-10-18", 1));
+    }
+}
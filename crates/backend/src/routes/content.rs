@@ -1,11 +1,75 @@
-use common::dtos::{ContentDto, CreateContentDto};
+use std::{env, num::NonZeroU32};
+
+use common::dtos::{
+    ContentDto, ContentType, CreateContentDto, CreateTextContentDto, CreateUrlContentDto,
+    ScreenLayoutDto, TextContentDto, VideoMetadataDto, ALLOWED_URL_SCHEMES,
+};
 use rocket::{
-    data::Capped, form::Form, fs::TempFile, response::status::Created, serde::json::Json, State,
+    data::Capped, fairing, form::Form, fs::TempFile, http, response::status::Created,
+    serde::json::Json, Build, Rocket, State,
 };
-use sea_orm::{ActiveModelTrait, EntityTrait, Set, TransactionTrait};
+use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set, TransactionTrait};
 use sea_orm_rocket::Connection;
 
-use crate::{auth::Session, error::AppError, files::Files, pool::Db};
+use crate::{
+    auth::Session,
+    error::AppError,
+    files::{self, Files},
+    media::{self, VideoMetadata},
+    notifier::FeedNotifier,
+    pool::Db,
+    span,
+};
+
+/// Most pages that an uploaded PDF may have.
+const PDF_MAX_PAGES: u32 = 20;
+
+/// Length of the longer side of the images that PDF pages are rendered to, in pixels.
+const PDF_RESOLUTION: u32 = 1920;
+
+/// Keeps the size of images, as they are only scaled down.
+const FULL_SIZE: media::Rendition = media::Rendition {
+    max_width: i32::MAX,
+    max_height: i32::MAX,
+};
+
+/// Size that thumbnails of images are scaled down to fit in, in pixels.
+const THUMBNAIL_SIZE: media::Rendition = media::Rendition {
+    max_width: 480,
+    max_height: 270,
+};
+
+/// Codecs of the videos which the screens can play, as named by `ffprobe`.
+const PLAYABLE_VIDEO_CODECS: [&str; 4] = ["h264", "vp8", "vp9", "av1"];
+
+/// Conversion of stored content to what is sent to the clients, which needs the urls of its files.
+pub(crate) trait FromContentModel {
+    fn from_model(files: &Files, content: entity::content::Model) -> Self;
+}
+
+impl FromContentModel for ContentDto {
+    fn from_model(files: &Files, content: entity::content::Model) -> Self {
+        ContentDto {
+            id: content.id,
+            screen: content.screen,
+            url: files.content_url(&content.content_type, &content.file_path),
+            content_type: content.content_type.into(),
+            archive_date: content.archive_date.map(|d| d.and_utc()),
+            text: TextContentDto::from_columns(
+                content.markdown,
+                content.background_color,
+                content.text_color,
+            ),
+            thumbnail_url: content.thumbnail_path.map(|path| files.file_url(&path)),
+            video: VideoMetadataDto::from_columns(
+                content.video_codec,
+                content.width,
+                content.height,
+                content.duration_ms,
+            ),
+        }
+    }
+}
 
 #[derive(FromForm)]
 pub(crate) struct Upload<'r> {
@@ -17,78 +81,529 @@ pub(crate) struct Upload<'r> {
 pub async fn create_content(
     _session: Session,
     conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
     files: &State<Files>,
     mut upload: Form<Upload<'_>>,
 ) -> Result<Created<Json<ContentDto>>, AppError> {
+    if matches!(
+        upload.data.content_type,
+        ContentType::Url | ContentType::Text
+    ) {
+        return Err(AppError::ContentNotUploadable);
+    }
+
     let db = conn.into_inner();
     let txn = db.begin().await?;
 
-    // ensure screen exists
-    entity::screen::Entity::find_by_id(upload.data.screen)
+    let screen = entity::screen::Entity::find_by_id(upload.data.screen)
         .one(&txn)
         .await?
         .ok_or_else(|| AppError::ScreenNotFound)?;
 
-    let key = files.upload_file(&mut upload.file).await?;
+    // videos are checked before storing them, as they may be rejected
+    let (video, poster_path) = match upload.data.content_type {
+        ContentType::Video => {
+            let (video, poster_path) = process_video(files, &upload.file).await?;
+            (Some(video), poster_path)
+        }
+        _ => (None, None),
+    };
+    let image_type = match upload.data.content_type {
+        ContentType::Image => processed_image_type(&upload.file),
+        _ => None,
+    };
+    let (file_path, rendition_path, thumbnail_path) = match image_type {
+        Some(image_type) => {
+            let content = files::read_file(&upload.file).await?;
+            let (key, rendition_path, thumbnail_path) =
+                upload_image(files, content, image_type, &screen).await?;
+            (key, Some(rendition_path), Some(thumbnail_path))
+        }
+        None => (
+            files.upload_file(&mut upload.file).await?.key,
+            None,
+            poster_path,
+        ),
+    };
 
     let res = entity::content::ActiveModel {
         slide: Set(None),
         screen: Set(upload.data.screen),
         content_type: Set(upload.data.content_type.into()),
-        file_path: Set(key),
+        file_path: Set(file_path),
+        duration_ms: Set(video.as_ref().map(|video| video.duration_ms)),
+        width: Set(video.as_ref().map(|video| video.width)),
+        height: Set(video.as_ref().map(|video| video.height)),
+        video_codec: Set(video.map(|video| video.codec)),
+        rendition_path: Set(rendition_path),
+        thumbnail_path: Set(thumbnail_path),
         ..Default::default()
     }
     .insert(&txn)
     .await?;
 
     txn.commit().await?;
+    notifier.notify();
 
     // NOTE: non-existent route
-    Ok(
-        Created::new(format!("/api/content/{}", res.id)).body(Json(ContentDto {
-            id: res.id,
-            screen: res.screen,
-            content_type: upload.data.content_type,
-            url: files.file_url(&res.file_path),
-            archive_date: None,
-        })),
+    Ok(Created::new(format!("/api/content/{}", res.id))
+        .body(Json(ContentDto::from_model(files, res))))
+}
+
+/// Type that an uploaded image is stored as once processed, or `None` if images of its type are
+/// stored as uploaded, e.g. GIFs which may be animated.
+pub(crate) fn processed_image_type(file: &Capped<TempFile<'_>>) -> Option<http::ContentType> {
+    match file.content_type() {
+        Some(ct) if *ct == http::ContentType::JPEG => Some(http::ContentType::JPEG),
+        Some(ct) if *ct == http::ContentType::PNG || *ct == http::ContentType::WEBP => {
+            Some(http::ContentType::PNG)
+        }
+        _ => None,
+    }
+}
+
+/// Store an image upright and without its metadata (such as the location a photo was taken at),
+/// along with a version of it scaled to fit the screen and a thumbnail of it, returning their keys
+/// in that order. Images which can't be processed are rejected, as their metadata can't be
+/// stripped.
+async fn upload_image(
+    files: &Files,
+    content: Vec<u8>,
+    content_type: http::ContentType,
+    screen: &entity::screen::Model,
+) -> Result<(String, String, String), AppError> {
+    let screen_size = media::Rendition {
+        max_width: screen.width,
+        max_height: screen.height,
+    };
+    let keys = upload_renditions(
+        files,
+        content,
+        content_type,
+        vec![FULL_SIZE, screen_size, THUMBNAIL_SIZE],
     )
+    .await?;
+    match <[String; 3]>::try_from(keys) {
+        Ok([key, rendition_key, thumbnail_key]) => Ok((key, rendition_key, thumbnail_key)),
+        Err(_) => Err(AppError::InternalError(
+            "image was not rendered at every size",
+        )),
+    }
+}
+
+/// Store an image upright and without its metadata scaled down to fit in each of the given sizes,
+/// returning the keys in the same order.
+pub(crate) async fn upload_renditions(
+    files: &Files,
+    content: Vec<u8>,
+    content_type: http::ContentType,
+    sizes: Vec<media::Rendition>,
+) -> Result<Vec<String>, AppError> {
+    let images =
+        media::render_image(content, sizes, content_type == http::ContentType::JPEG).await?;
+
+    let mut keys = Vec::with_capacity(images.len());
+    for image in images {
+        keys.push(files.upload(image, Some(&content_type)).await?.key);
+    }
+    Ok(keys)
+}
+
+/// Read the metadata of an uploaded video and store a poster frame of it, returning the metadata
+/// and the key of the poster. Videos whose metadata can't be read are rejected, as they couldn't be
+/// shown for their whole length, along with videos using a codec which isn't in
+/// [`PLAYABLE_VIDEO_CODECS`].
+async fn process_video(
+    files: &Files,
+    file: &Capped<TempFile<'_>>,
+) -> Result<(VideoMetadata, Option<String>), AppError> {
+    let content = files::read_file(file).await?;
+    let video = media::probe_video(content.clone()).await?;
+    if !is_playable(&video.codec) {
+        return Err(AppError::UnsupportedVideoCodec(video.codec));
+    }
+
+    let poster_path = match media::extract_poster(content, THUMBNAIL_SIZE).await {
+        Ok(poster) => Some(
+            files
+                .upload(poster, Some(&http::ContentType::JPEG))
+                .await?
+                .key,
+        ),
+        Err(err) => {
+            warn!("Could not extract poster of uploaded video: {err}");
+            None
+        }
+    };
+
+    Ok((video, poster_path))
+}
+
+/// Whether the screens can play videos using the given codec, as named by `ffprobe`.
+fn is_playable(codec: &str) -> bool {
+    PLAYABLE_VIDEO_CODECS.contains(&codec)
+}
+
+/// Create content showing an external web page, which must use one of [`ALLOWED_URL_SCHEMES`].
+#[post("/content/url", data = "<data>")]
+pub async fn create_url_content(
+    _session: Session,
+    conn: Connection<'_, Db>,
+    files: &State<Files>,
+    notifier: &State<FeedNotifier>,
+    data: Json<CreateUrlContentDto>,
+) -> Result<Created<Json<ContentDto>>, AppError> {
+    let url = parse_url(&data.url).ok_or(AppError::InvalidUrl)?;
+
+    let db = conn.into_inner();
+    // ensure screen exists
+    entity::screen::Entity::find_by_id(data.screen)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::ScreenNotFound)?;
+
+    let res = entity::content::ActiveModel {
+        slide: Set(None),
+        screen: Set(data.screen),
+        content_type: Set(ContentType::Url.into()),
+        file_path: Set(url),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    notifier.notify();
+
+    // NOTE: non-existent route
+    Ok(Created::new(format!("/api/content/{}", res.id))
+        .body(Json(ContentDto::from_model(files, res))))
+}
+
+#[derive(FromForm)]
+pub(crate) struct PdfUpload<'r> {
+    screen: i32,
+    file: Capped<TempFile<'r>>,
+}
+
+/// Render every page of a PDF as an image, creating content on the given screen for each of them,
+/// in the order of the pages. The content is meant to be put on a slide each.
+///
+/// The number of pages and the resolution can be configured with `PDF_MAX_PAGES` and
+/// `PDF_RESOLUTION`.
+#[post("/content/pdf", data = "<upload>")]
+pub async fn create_pdf_content(
+    _session: Session,
+    conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
+    files: &State<Files>,
+    upload: Form<PdfUpload<'_>>,
+) -> Result<Created<Json<Vec<ContentDto>>>, AppError> {
+    if upload.file.content_type() != Some(&http::ContentType::PDF) {
+        return Err(AppError::PdfRequired);
+    }
+
+    let db = conn.into_inner();
+    let screen = entity::screen::Entity::find_by_id(upload.screen)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::ScreenNotFound)?;
+
+    let content = files::read_file(&upload.file).await?;
+    let pages = media::rasterize_pdf(content, pdf_max_pages(), pdf_resolution()).await?;
+
+    // files are uploaded before the transaction, as in `create_content`
+    let mut keys = Vec::with_capacity(pages.len());
+    for page in pages {
+        keys.push(upload_image(files, page, http::ContentType::PNG, &screen).await?);
+    }
+
+    let txn = db.begin().await?;
+    let mut created = Vec::with_capacity(keys.len());
+    for (key, rendition_key, thumbnail_key) in keys {
+        let res = entity::content::ActiveModel {
+            slide: Set(None),
+            screen: Set(upload.screen),
+            content_type: Set(ContentType::Image.into()),
+            file_path: Set(key),
+            rendition_path: Set(Some(rendition_key)),
+            thumbnail_path: Set(Some(thumbnail_key)),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        created.push(ContentDto::from_model(files, res));
+    }
+    txn.commit().await?;
+    notifier.notify();
+
+    // NOTE: non-existent route
+    Ok(Created::new("/api/content").body(Json(created)))
+}
+
+fn pdf_max_pages() -> u32 {
+    positive_env_var("PDF_MAX_PAGES").unwrap_or(PDF_MAX_PAGES)
+}
+
+fn pdf_resolution() -> u32 {
+    positive_env_var("PDF_RESOLUTION").unwrap_or(PDF_RESOLUTION)
+}
+
+/// The value of an environment variable, if it is set to a positive number.
+fn positive_env_var(name: &str) -> Option<u32> {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse::<NonZeroU32>().ok())
+        .map(NonZeroU32::get)
+}
+
+/// Refuse to start if `PDF_MAX_PAGES` or `PDF_RESOLUTION` is set to something else than a positive
+/// number, e.g. a resolution of 0 which no page could be rendered at.
+pub(crate) async fn check_pdf_config(rocket: Rocket<Build>) -> fairing::Result {
+    for name in ["PDF_MAX_PAGES", "PDF_RESOLUTION"] {
+        if env::var(name).is_ok() && positive_env_var(name).is_none() {
+            error!("{name} must be a positive number");
+            return Err(rocket);
+        }
+    }
+    Ok(rocket)
+}
+
+#[derive(FromForm)]
+pub(crate) struct TextUpload<'r> {
+    data: Json<CreateTextContentDto>,
+    background: Option<Capped<TempFile<'r>>>,
+}
+
+/// Create content showing markdown, with an optional background image.
+#[post("/content/text", data = "<upload>")]
+pub async fn create_text_content(
+    _session: Session,
+    conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
+    files: &State<Files>,
+    mut upload: Form<TextUpload<'_>>,
+) -> Result<Created<Json<ContentDto>>, AppError> {
+    let text = upload.data.text.clone();
+    // colors are put in style attributes by the screens, so they must not contain anything else
+    if !is_valid_color(&text.background_color) || !is_valid_color(&text.text_color) {
+        return Err(AppError::InvalidColor);
+    }
+
+    let db = conn.into_inner();
+    // the screen is looked up first, so that no background is stored for an unknown screen
+    let screen = entity::screen::Entity::find_by_id(upload.data.screen)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::ScreenNotFound)?;
+
+    let (file_path, rendition_path, thumbnail_path) = match upload.background.as_mut() {
+        Some(background) => {
+            let is_image = background
+                .content_type()
+                .is_some_and(|ct| ct.top() == "image");
+            if !is_image {
+                return Err(AppError::BackgroundRequiresImage);
+            }
+            match processed_image_type(background) {
+                Some(image_type) => {
+                    let content = files::read_file(background).await?;
+                    let (key, rendition_path, thumbnail_path) =
+                        upload_image(files, content, image_type, &screen).await?;
+                    (key, Some(rendition_path), Some(thumbnail_path))
+                }
+                None => (files.upload_file(background).await?.key, None, None),
+            }
+        }
+        None => (String::new(), None, None),
+    };
+
+    let res = entity::content::ActiveModel {
+        slide: Set(None),
+        screen: Set(upload.data.screen),
+        content_type: Set(ContentType::Text.into()),
+        file_path: Set(file_path),
+        rendition_path: Set(rendition_path),
+        thumbnail_path: Set(thumbnail_path),
+        markdown: Set(Some(text.markdown.clone())),
+        background_color: Set(Some(text.background_color.clone())),
+        text_color: Set(Some(text.text_color.clone())),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    notifier.notify();
+
+    // NOTE: non-existent route
+    Ok(Created::new(format!("/api/content/{}", res.id))
+        .body(Json(ContentDto::from_model(files, res))))
+}
+
+/// Whether a color is in the format `#rrggbb`.
+fn is_valid_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Normalize the url of a web page, which is `None` if it isn't valid.
+fn parse_url(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url.trim()).ok()?;
+    if !ALLOWED_URL_SCHEMES.contains(&url.scheme()) || !url.has_host() {
+        return None;
+    }
+    Some(url.into())
+}
+
+#[derive(FromForm)]
+pub(crate) struct SpanUpload<'r> {
+    file: Capped<TempFile<'r>>,
+}
+
+/// Split an image across all screens, creating content for each screen with the part of the image
+/// shown on it, based on the position and layout of the screens. The content is returned in the
+/// order of the screens.
+#[post("/content/span", data = "<upload>")]
+pub async fn create_span_content(
+    _session: Session,
+    conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
+    files: &State<Files>,
+    upload: Form<SpanUpload<'_>>,
+) -> Result<Created<Json<Vec<ContentDto>>>, AppError> {
+    let is_image = upload
+        .file
+        .content_type()
+        .is_some_and(|ct| ct.top() == "image");
+    if !is_image {
+        return Err(AppError::SpanRequiresImage);
+    }
+    let content = files::read_file(&upload.file).await?;
+
+    let db = conn.into_inner();
+    let screens = entity::screen::Entity::find()
+        .order_by_asc(entity::screen::Column::Position)
+        .all(db)
+        .await?;
+    let layouts: Vec<ScreenLayoutDto> = screens
+        .iter()
+        .map(|screen| ScreenLayoutDto {
+            width: screen.width,
+            height: screen.height,
+            bezel_gap: screen.bezel_gap,
+        })
+        .collect();
+
+    let (width, height) = media::probe_image(content.clone()).await?;
+    let crops = media::crop_image(content, span::span_crops(width, height, &layouts)).await?;
+
+    // files are uploaded before the transaction, as in `create_content`
+    let mut keys = Vec::with_capacity(crops.len());
+    for (screen, crop) in screens.iter().zip(crops) {
+        keys.push(upload_image(files, crop, http::ContentType::PNG, screen).await?);
+    }
+
+    let txn = db.begin().await?;
+    let mut created = Vec::with_capacity(keys.len());
+    for (screen, (key, rendition_key, thumbnail_key)) in screens.iter().zip(keys) {
+        let res = entity::content::ActiveModel {
+            slide: Set(None),
+            screen: Set(screen.id),
+            content_type: Set(ContentType::Image.into()),
+            file_path: Set(key),
+            rendition_path: Set(Some(rendition_key)),
+            thumbnail_path: Set(Some(thumbnail_key)),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        created.push(ContentDto::from_model(files, res));
+    }
+    txn.commit().await?;
+    notifier.notify();
+
+    // NOTE: non-existent route
+    Ok(Created::new("/api/content").body(Json(created)))
 }
 
 #[cfg(test)]
 mod tests {
     use common::dtos::{
-        AppErrorDto, ContentDto, ContentType, CreateContentDto, OwnerDto, SlideDto, SlideGroupDto,
+        AppErrorDto, ContentDto, ContentType, CreateContentDto, CreateTextContentDto,
+        CreateUrlContentDto, OwnerDto, SlideDto, SlideGroupDto, TextContentDto,
     };
     use rocket::http::{self, Status};
     use rocket::serde::json;
     use sea_orm::prelude::DateTimeUtc;
 
-    use crate::assert_created;
+    use super::{is_playable, is_valid_color, parse_url, pdf_max_pages};
+    use crate::error::AppError;
     use crate::test_utils::{util_create_slide, util_create_slide_group, TestClient};
+    use crate::{assert_app_error, assert_created};
 
-    fn util_prepare_upload(data: &CreateContentDto, file: &str) -> (http::ContentType, String) {
+    /// Build a multipart form of the given parts, as `(name, content_type, body)`. Parts with a
+    /// content type are sent as files.
+    fn util_multipart(parts: &[(&str, Option<&str>, &str)]) -> (http::ContentType, String) {
         // There isn't a better way to test this :/
         // https://github.com/rwf2/Rocket/issues/1591
         let ct = "multipart/form-data; boundary=X-BOUNDARY"
             .parse::<http::ContentType>()
             .unwrap();
-        let body = [
-            "--X-BOUNDARY",
-            r#"Content-Disposition: form-data; name="data""#,
-            "",
-            &json::to_string(data).unwrap(),
-            "--X-BOUNDARY",
-            r#"Content-Disposition: form-data; name="file"; filename="foo.html""#,
-            "Content-Type: text/html",
-            "",
-            file,
-            "--X-BOUNDARY--",
-            "",
-        ]
-        .join("\r\n");
+        let mut lines = vec![];
+        for (name, content_type, body) in parts {
+            lines.push("--X-BOUNDARY".to_string());
+            match content_type {
+                Some(content_type) => {
+                    lines.push(format!(
+                        r#"Content-Disposition: form-data; name="{name}"; filename="{name}""#
+                    ));
+                    lines.push(format!("Content-Type: {content_type}"));
+                }
+                None => lines.push(format!(r#"Content-Disposition: form-data; name="{name}""#)),
+            }
+            lines.push(String::new());
+            lines.push(body.to_string());
+        }
+        lines.push("--X-BOUNDARY--".to_string());
+        lines.push(String::new());
 
-        (ct, body)
+        (ct, lines.join("\r\n"))
+    }
+
+    fn util_prepare_upload(data: &CreateContentDto, file: &str) -> (http::ContentType, String) {
+        util_multipart(&[
+            ("data", None, &json::to_string(data).unwrap()),
+            ("file", Some("text/html"), file),
+        ])
+    }
+
+    /// Build a PDF with the given number of blank pages.
+    fn util_pdf(pages: usize) -> String {
+        let kids: Vec<String> = (0..pages).map(|page| format!("{} 0 R", page + 3)).collect();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {pages} >>",
+                kids.join(" ")
+            ),
+        ];
+        objects.extend(
+            (0..pages)
+                .map(|_| "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 200 100] >>".to_string()),
+        );
+
+        let mut pdf = "%PDF-1.4\n".to_string();
+        let mut offsets = vec![];
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf += &format!("{} 0 obj\n{object}\nendobj\n", index + 1);
+        }
+        let xref = pdf.len();
+        pdf += &format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            pdf += &format!("{offset:010} 00000 n \n");
+        }
+        pdf += &format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        pdf
     }
 
     #[test]
@@ -123,16 +638,21 @@ mod tests {
                 id: 1,
                 title: "Lorem Ipsum".to_string(),
                 priority: 0,
+                takeover: false,
                 hidden: false,
                 created_by: OwnerDto::User("johndoe".to_string()),
                 start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
                 end_date: None,
+                recurrence: None,
+                transition: None,
+                screens: vec![],
                 archive_date: None,
                 published: false,
                 slides: vec![SlideDto {
                     id: 1,
                     position: 1,
                     archive_date: None,
+                    duration: None,
                     content: vec![
                         ContentDto {
                             id: 1,
@@ -140,6 +660,9 @@ mod tests {
                             content_type: ContentType::Html,
                             url: "82/8286230721b68e5d0e15dabf39d5938611b053c320f95ed8a4fa556fd41e7457.html".to_string(),
                             archive_date: None,
+                            text: None,
+                            thumbnail_url: None,
+                            video: None,
                         },
                         ContentDto {
                             id: 2,
@@ -147,6 +670,9 @@ mod tests {
                             content_type: ContentType::Html,
                             url: "39/39acde5bed34e4bd9a0374f628ea4c64fb515f92a9aa981e8a8d8414ef9ad799.html".to_string(),
                             archive_date: None,
+                            text: None,
+                            thumbnail_url: None,
+                            video: None,
                         }
                     ]
                 },],
@@ -204,22 +730,30 @@ mod tests {
                 id: 1,
                 title: "Lorem Ipsum".to_string(),
                 priority: 0,
+                takeover: false,
                 hidden: false,
                 created_by: OwnerDto::User("johndoe".to_string()),
                 start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
                 end_date: None,
+                recurrence: None,
+                transition: None,
+                screens: vec![],
                 archive_date: None,
                 published: false,
                 slides: vec![SlideDto {
                     id: 1,
                     position: 1,
                     archive_date: None,
+                    duration: None,
                     content: vec![ContentDto {
                         id: 2,
                         screen: 1,
                         content_type: ContentType::Html,
                         url: "39/39acde5bed34e4bd9a0374f628ea4c64fb515f92a9aa981e8a8d8414ef9ad799.html".to_string(),
                         archive_date: None,
+                        text: None,
+                        thumbnail_url: None,
+                        video: None,
                     }]
                 },],
             }])
@@ -294,5 +828,264 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unreadable_video() {
+        let mut client = TestClient::new();
+        client.login_as("johndoe", false);
+
+        // requires ffprobe, which fails to read the file
+        let data = CreateContentDto {
+            screen: 1,
+            content_type: ContentType::Video,
+        };
+        let (ct, body) = util_prepare_upload(&data, "<p>hello world</p>");
+        let response = client.post("/api/content").header(ct).body(body).dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[test]
+    fn unreadable_image() {
+        let mut client = TestClient::new();
+        client.login_as("johndoe", false);
+
+        // requires ffmpeg, which fails to read the file, so its metadata can't be stripped
+        let data = CreateContentDto {
+            screen: 1,
+            content_type: ContentType::Image,
+        };
+        let (ct, body) = util_multipart(&[
+            ("data", None, &json::to_string(&data).unwrap()),
+            ("file", Some("image/jpeg"), "<p>hello world</p>"),
+        ]);
+        let response = client.post("/api/content").header(ct).body(body).dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[test]
+    fn playable_codecs() {
+        for codec in ["h264", "vp8", "vp9", "av1"] {
+            assert!(is_playable(codec), "{codec} isn't playable");
+        }
+        for codec in ["hevc", "mpeg4", "prores", "H264", ""] {
+            assert!(!is_playable(codec), "{codec} is playable");
+        }
+    }
+
+    #[test]
+    fn span_requires_image() {
+        let mut client = TestClient::new();
+        client.login_as("johndoe", false);
+
+        let (ct, body) = util_multipart(&[("file", Some("text/html"), "<p>hello world</p>")]);
+        let response = client
+            .post("/api/content/span")
+            .header(ct)
+            .body(body)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        assert_eq!(
+            response.into_json(),
+            Some(AppErrorDto {
+                msg: "only images can be spanned across screens".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn url_schemes() {
+        assert_eq!(
+            parse_url(" https://example.com/results?round=2 "),
+            Some("https://example.com/results?round=2".to_string())
+        );
+        assert_eq!(
+            parse_url("http://example.com"),
+            Some("http://example.com/".to_string())
+        );
+        for url in [
+            "javascript:alert(1)",
+            "file:///etc/passwd",
+            "data:text/html,<p>hi</p>",
+            "example.com",
+            "",
+        ] {
+            assert_eq!(parse_url(url), None, "{url} is allowed");
+        }
+    }
+
+    #[test]
+    fn create_url_content() {
+        let mut client = TestClient::new();
+        client.login_as("johndoe", false);
+
+        let data = CreateUrlContentDto {
+            screen: 1,
+            url: "javascript:alert(1)".to_string(),
+        };
+        let response = client.post("/api/content/url").json(&data).dispatch();
+        assert_app_error!(response, AppError::InvalidUrl);
+
+        let data = CreateUrlContentDto {
+            screen: 42,
+            url: "https://example.com/".to_string(),
+        };
+        let response = client.post("/api/content/url").json(&data).dispatch();
+        assert_app_error!(response, AppError::ScreenNotFound);
+
+        let data = CreateUrlContentDto {
+            screen: 1,
+            url: "https://example.com/".to_string(),
+        };
+        let response = client.post("/api/content/url").json(&data).dispatch();
+        assert_eq!(response.status(), Status::Created);
+        assert_eq!(
+            response.into_json(),
+            Some(ContentDto {
+                id: 1,
+                screen: 1,
+                content_type: ContentType::Url,
+                url: "https://example.com/".to_string(),
+                archive_date: None,
+                text: None,
+                thumbnail_url: None,
+                video: None,
+            })
+        );
+
+        // web pages can't be uploaded
+        let data = CreateContentDto {
+            screen: 1,
+            content_type: ContentType::Url,
+        };
+        let (ct, body) = util_prepare_upload(&data, "https://example.com/");
+        let response = client.post("/api/content").header(ct).body(body).dispatch();
+        assert_app_error!(response, AppError::ContentNotUploadable);
+    }
+
+    #[test]
+    fn colors() {
+        assert!(is_valid_color("#00ff7F"));
+        for color in [
+            "red",
+            "#fff",
+            "#00ff7",
+            "#00ff7f0",
+            "#00gg00",
+            "#00ff7f;x",
+            "",
+        ] {
+            assert!(!is_valid_color(color), "{color} is allowed");
+        }
+    }
+
+    #[test]
+    fn create_text_content() {
+        let mut client = TestClient::new();
+        client.login_as("johndoe", false);
+
+        let body = |data: &CreateTextContentDto| {
+            util_multipart(&[("data", None, &json::to_string(data).unwrap())])
+        };
+
+        let mut data = CreateTextContentDto {
+            screen: 1,
+            text: TextContentDto {
+                markdown: "# Pub crawl\n\nFriday at *18:00*".to_string(),
+                background_color: "red".to_string(),
+                text_color: "#ffffff".to_string(),
+            },
+        };
+        let (ct, form) = body(&data);
+        let response = client
+            .post("/api/content/text")
+            .header(ct)
+            .body(form)
+            .dispatch();
+        assert_app_error!(response, AppError::InvalidColor);
+
+        data.text.background_color = "#1e1e1e".to_string();
+        let (ct, form) = body(&data);
+        let response = client
+            .post("/api/content/text")
+            .header(ct)
+            .body(form)
+            .dispatch();
+        assert_eq!(response.status(), Status::Created);
+        assert_eq!(
+            response.into_json(),
+            Some(ContentDto {
+                id: 1,
+                screen: 1,
+                content_type: ContentType::Text,
+                url: "".to_string(),
+                archive_date: None,
+                text: Some(data.text),
+                thumbnail_url: None,
+                video: None,
+            })
+        );
+    }
+
+    #[test]
+    fn unreadable_background() {
+        let mut client = TestClient::new();
+        client.login_as("johndoe", false);
+
+        // requires ffmpeg, which fails to read the file, so its metadata can't be stripped
+        let data = CreateTextContentDto {
+            screen: 1,
+            text: TextContentDto::default(),
+        };
+        let (ct, body) = util_multipart(&[
+            ("data", None, &json::to_string(&data).unwrap()),
+            ("background", Some("image/jpeg"), "<p>hello world</p>"),
+        ]);
+        let response = client
+            .post("/api/content/text")
+            .header(ct)
+            .body(body)
+            .dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[test]
+    fn pdf_required() {
+        let mut client = TestClient::new();
+        client.login_as("johndoe", false);
+
+        let (ct, body) = util_multipart(&[
+            ("screen", None, "1"),
+            ("file", Some("text/html"), "<p>hello world</p>"),
+        ]);
+        let response = client
+            .post("/api/content/pdf")
+            .header(ct)
+            .body(body)
+            .dispatch();
+        assert_app_error!(response, AppError::PdfRequired);
+    }
+
+    #[test]
+    fn too_many_pages() {
+        let mut client = TestClient::new();
+        client.login_as("johndoe", false);
+
+        // requires pdfinfo, which counts the pages
+        let max_pages = pdf_max_pages();
+        let (ct, body) = util_multipart(&[
+            ("screen", None, "1"),
+            (
+                "file",
+                Some("application/pdf"),
+                &util_pdf(max_pages as usize + 1),
+            ),
+        ]);
+        let response = client
+            .post("/api/content/pdf")
+            .header(ct)
+            .body(body)
+            .dispatch();
+        assert_app_error!(response, AppError::TooManyPages(max_pages));
+    }
+
     // TODO: test archived slides/slide groups
 }
@@ -0,0 +1,293 @@
+use common::dtos::{CreateEmergencyMessageDto, EmergencyMessageDto};
+use rocket::{
+    data::Capped, form::Form, fs::TempFile, http::Status, response::status::Created,
+    serde::json::Json, State,
+};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait,
+    QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use sea_orm_rocket::Connection;
+
+use crate::{
+    auth::Session,
+    error::AppError,
+    files::{self, Files},
+    media,
+    notifier::FeedNotifier,
+    pool::Db,
+    routes::content::{processed_image_type, upload_renditions},
+};
+
+#[derive(FromForm)]
+pub(crate) struct EmergencyUpload<'r> {
+    data: Json<CreateEmergencyMessageDto>,
+    image: Option<Capped<TempFile<'r>>>,
+}
+
+/// Get the emergency message which is currently shown on the screens, if any.
+#[get("/emergency")]
+pub async fn get_emergency_message(
+    conn: Connection<'_, Db>,
+    files: &State<Files>,
+) -> Result<Json<Option<EmergencyMessageDto>>, AppError> {
+    let db = conn.into_inner();
+
+    Ok(Json(active_emergency_message(db, files).await?))
+}
+
+/// Show a message on every screen instead of their feed, replacing any current message. Only
+/// admins can raise emergency messages.
+#[post("/emergency", data = "<upload>")]
+pub async fn raise_emergency_message(
+    session: Session,
+    conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
+    files: &State<Files>,
+    mut upload: Form<EmergencyUpload<'_>>,
+) -> Result<Created<Json<EmergencyMessageDto>>, AppError> {
+    if !session.is_admin {
+        return Err(AppError::Unauthorized);
+    }
+    if upload.data.message.trim().is_empty() {
+        return Err(AppError::EmptyEmergencyMessage);
+    }
+
+    let db = conn.into_inner();
+    let file_path = match upload.image.as_mut() {
+        Some(image) => Some(upload_emergency_image(db, files, image).await?),
+        None => None,
+    };
+
+    let txn = db.begin().await?;
+    let now = chrono::Utc::now().naive_utc();
+
+    clear_active_messages(&txn).await?;
+    let res = entity::emergency_message::ActiveModel {
+        message: Set(upload.data.message.clone()),
+        file_path: Set(file_path),
+        created_by: Set(session.username.clone()),
+        created_at: Set(now),
+        expires_at: Set(upload.data.expires_at.map(|date| date.naive_utc())),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+    notifier.notify();
+
+    Ok(Created::new(format!("/api/emergency/{}", res.id))
+        .body(Json(emergency_message_dto(res, files))))
+}
+
+/// Store the image of an emergency message, returning its key. As the message is shown on every
+/// screen, the image is scaled down to fit the largest one.
+async fn upload_emergency_image(
+    db: &impl ConnectionTrait,
+    files: &Files,
+    image: &mut Capped<TempFile<'_>>,
+) -> Result<String, AppError> {
+    let is_image = image.content_type().is_some_and(|ct| ct.top() == "image");
+    if !is_image {
+        return Err(AppError::ImageRequired);
+    }
+    let Some(image_type) = processed_image_type(image) else {
+        return Ok(files.upload_file(image).await?.key);
+    };
+
+    let screens = entity::screen::Entity::find().all(db).await?;
+    let size = media::Rendition {
+        max_width: screens
+            .iter()
+            .map(|screen| screen.width)
+            .max()
+            .unwrap_or(i32::MAX),
+        max_height: screens
+            .iter()
+            .map(|screen| screen.height)
+            .max()
+            .unwrap_or(i32::MAX),
+    };
+    let content = files::read_file(image).await?;
+    upload_renditions(files, content, image_type, vec![size])
+        .await?
+        .pop()
+        .ok_or(AppError::InternalError("image was not rendered"))
+}
+
+/// Clear the current emergency message, so that the screens resume showing their feed.
+#[delete("/emergency")]
+pub async fn clear_emergency_message(
+    session: Session,
+    conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
+) -> Result<Status, AppError> {
+    if !session.is_admin {
+        return Err(AppError::Unauthorized);
+    }
+
+    let db = conn.into_inner();
+    clear_active_messages(db).await?;
+    notifier.notify();
+
+    Ok(Status::NoContent)
+}
+
+/// The latest emergency message which hasn't been cleared or expired.
+pub(crate) async fn active_emergency_message(
+    db: &impl ConnectionTrait,
+    files: &Files,
+) -> Result<Option<EmergencyMessageDto>, AppError> {
+    let now = chrono::Utc::now().naive_utc();
+
+    let message = entity::emergency_message::Entity::find()
+        .filter(entity::emergency_message::Column::ClearedAt.is_null())
+        .filter(
+            Condition::any()
+                .add(entity::emergency_message::Column::ExpiresAt.is_null())
+                .add(entity::emergency_message::Column::ExpiresAt.gt(now)),
+        )
+        .order_by_desc(entity::emergency_message::Column::Id)
+        .one(db)
+        .await?;
+
+    Ok(message.map(|message| emergency_message_dto(message, files)))
+}
+
+async fn clear_active_messages(db: &impl ConnectionTrait) -> Result<(), AppError> {
+    let now = chrono::Utc::now().naive_utc();
+
+    entity::emergency_message::Entity::update_many()
+        .col_expr(
+            entity::emergency_message::Column::ClearedAt,
+            Expr::value(now),
+        )
+        .filter(entity::emergency_message::Column::ClearedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+fn emergency_message_dto(
+    message: entity::emergency_message::Model,
+    files: &Files,
+) -> EmergencyMessageDto {
+    EmergencyMessageDto {
+        id: message.id,
+        message: message.message,
+        image_url: message.file_path.map(|key| files.file_url(&key)),
+        created_at: message.created_at.and_utc(),
+        expires_at: message.expires_at.map(|date| date.and_utc()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::dtos::{CreateEmergencyMessageDto, EmergencyMessageDto};
+    use rocket::http::{self, Status};
+    use rocket::serde::json;
+
+    use crate::{assert_app_error, error::AppError, test_utils::TestClient};
+
+    fn util_prepare_upload(
+        data: &CreateEmergencyMessageDto,
+        image: Option<(&str, &str)>,
+    ) -> (http::ContentType, String) {
+        let ct = "multipart/form-data; boundary=X-BOUNDARY"
+            .parse::<http::ContentType>()
+            .unwrap();
+        let mut lines = vec![
+            "--X-BOUNDARY".to_string(),
+            r#"Content-Disposition: form-data; name="data""#.to_string(),
+            String::new(),
+            json::to_string(data).unwrap(),
+        ];
+        if let Some((content_type, image)) = image {
+            lines.push("--X-BOUNDARY".to_string());
+            lines.push(
+                r#"Content-Disposition: form-data; name="image"; filename="image""#.to_string(),
+            );
+            lines.push(format!("Content-Type: {content_type}"));
+            lines.push(String::new());
+            lines.push(image.to_string());
+        }
+        lines.push("--X-BOUNDARY--".to_string());
+        lines.push(String::new());
+
+        (ct, lines.join("\r\n"))
+    }
+
+    #[test]
+    fn raise_and_clear_emergency_message() {
+        let mut client = TestClient::new();
+        let data = CreateEmergencyMessageDto {
+            message: "Evacuate the building".to_string(),
+            expires_at: None,
+        };
+
+        client.login_as("johndoe", false);
+        let (ct, body) = util_prepare_upload(&data, None);
+        let response = client
+            .post("/api/emergency")
+            .header(ct)
+            .body(body)
+            .dispatch();
+        assert_app_error!(response, AppError::Unauthorized);
+
+        client.login_as("admin", true);
+        let (ct, body) = util_prepare_upload(&data, None);
+        let response = client
+            .post("/api/emergency")
+            .header(ct)
+            .body(body)
+            .dispatch();
+        assert_eq!(response.status(), Status::Created);
+
+        let response = client.get("/api/emergency").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let message = response
+            .into_json::<Option<EmergencyMessageDto>>()
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.message, "Evacuate the building");
+        assert_eq!(message.image_url, None);
+
+        let response = client.delete("/api/emergency").dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+
+        let response = client.get("/api/emergency").dispatch();
+        assert_eq!(
+            response.into_json::<Option<EmergencyMessageDto>>(),
+            Some(None)
+        );
+    }
+
+    #[test]
+    fn image_required() {
+        let mut client = TestClient::new();
+        client.login_as("admin", true);
+
+        let data = CreateEmergencyMessageDto {
+            message: "Evacuate the building".to_string(),
+            expires_at: None,
+        };
+        let (ct, body) = util_prepare_upload(&data, Some(("text/html", "<p>hello world</p>")));
+        let response = client
+            .post("/api/emergency")
+            .header(ct)
+            .body(body)
+            .dispatch();
+        assert_app_error!(response, AppError::ImageRequired);
+
+        // requires ffmpeg, which fails to read the file, so its metadata can't be stripped
+        let (ct, body) = util_prepare_upload(&data, Some(("image/jpeg", "<p>hello world</p>")));
+        let response = client
+            .post("/api/emergency")
+            .header(ct)
+            .body(body)
+            .dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+}
@@ -3,11 +3,13 @@ use rocket::{response::status::Created, serde::json::Json};
 
 pub mod auth;
 pub mod content;
+pub mod emergency;
 pub mod health;
 pub mod screen;
 pub mod screen_feed;
 pub mod slide;
 pub mod slide_group;
+pub mod time;
 
 type CreatedResponse = Created<Json<CreatedDto>>;
 
@@ -1,9 +1,16 @@
-use common::dtos::{CreateScreenDto, ScreenDto};
-use rocket::serde::json::Json;
-use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set};
+use common::dtos::{
+    ContentDto, CreateScreenDto, EditScreenDto, FallbackContentDto, ScreenCommandDto,
+    ScreenConnectionDto, ScreenDto, ScreenStatusDto,
+};
+use rocket::{http::Status, serde::json::Json, State};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
 use sea_orm_rocket::Connection;
 
-use crate::{auth::Session, error::AppError, pool::Db};
+use crate::{
+    auth::Session, error::AppError, files::Files, notifier::FeedNotifier, pool::Db,
+    routes::content::FromContentModel, screen_commands::ScreenCommand,
+    screen_registry::ScreenRegistry,
+};
 
 use super::{build_created_response, CreatedResponse};
 
@@ -22,6 +29,46 @@ pub async fn list_screens(conn: Connection<'_, Db>) -> Result<Json<Vec<ScreenDto
     Ok(Json(screens))
 }
 
+/// Get which screens have clients connected to their feed, and since when the others have been
+/// disconnected. Only admins can see the status of screens.
+#[get("/screen/status")]
+pub async fn list_screen_statuses(
+    session: Session,
+    conn: Connection<'_, Db>,
+    registry: &State<ScreenRegistry>,
+) -> Result<Json<Vec<ScreenStatusDto>>, AppError> {
+    if !session.is_admin {
+        return Err(AppError::Unauthorized);
+    }
+
+    let db = conn.into_inner();
+    let screens = entity::screen::Entity::find()
+        .order_by_asc(entity::screen::Column::Position)
+        .all(db)
+        .await?;
+
+    let connections = registry.connections();
+    let statuses = screens
+        .into_iter()
+        .map(|screen| ScreenStatusDto {
+            screen: screen.id,
+            connections: connections
+                .iter()
+                .filter(|connection| connection.screen == screen.id)
+                .map(|connection| ScreenConnectionDto {
+                    remote_address: connection.remote_address.clone(),
+                    user_agent: connection.user_agent.clone(),
+                    connected_since: connection.connected_since,
+                    last_event: connection.last_event,
+                })
+                .collect(),
+            disconnected_since: registry.disconnected_since(screen.id),
+        })
+        .collect();
+
+    Ok(Json(statuses))
+}
+
 #[post("/screen", data = "<screen>")]
 pub async fn create_screen(
     _session: Session,
@@ -42,12 +89,169 @@ pub async fn create_screen(
     Ok(build_created_response("/api/screen", res.id))
 }
 
+/// Only admins can change screens, as they are shared by everyone.
+#[put("/screen/<id>", data = "<screen>")]
+pub async fn update_screen(
+    session: Session,
+    conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
+    id: i32,
+    screen: Json<EditScreenDto>,
+) -> Result<Status, AppError> {
+    if !session.is_admin {
+        return Err(AppError::Unauthorized);
+    }
+
+    let db = conn.into_inner();
+
+    entity::screen::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(AppError::ScreenNotFound)?;
+
+    entity::screen::ActiveModel {
+        id: Set(id),
+        name: Set(screen.name.to_string()),
+        position: Set(screen.position),
+        transition: Set(screen.transition.transition_type.into()),
+        transition_duration_ms: Set(screen.transition.duration.max(0)),
+        width: Set(screen.layout.width.max(1)),
+        height: Set(screen.layout.height.max(1)),
+        bezel_gap: Set(screen.layout.bezel_gap.max(0)),
+        ..Default::default()
+    }
+    .update(db)
+    .await?;
+    notifier.notify();
+
+    Ok(Status::NoContent)
+}
+
+/// Get the content shown on the screen when no slide group is to be shown, if any.
+#[get("/screen/<id>/fallback-content")]
+pub async fn get_fallback_content(
+    conn: Connection<'_, Db>,
+    files: &State<Files>,
+    id: i32,
+) -> Result<Json<Option<ContentDto>>, AppError> {
+    let db = conn.into_inner();
+
+    let screen = entity::screen::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(AppError::ScreenNotFound)?;
+    let Some(content_id) = screen.fallback_content else {
+        return Ok(Json(None));
+    };
+
+    let content = entity::content::Entity::find_by_id(content_id)
+        .filter(entity::content::Column::ArchiveDate.is_null())
+        .one(db)
+        .await?
+        .map(|content| ContentDto::from_model(files, content));
+
+    Ok(Json(content))
+}
+
+/// Set the content shown on the screen when no slide group is to be shown. The content must have
+/// been uploaded for the same screen. Only admins can change the fallback content.
+#[put("/screen/<id>/fallback-content", data = "<fallback>")]
+pub async fn set_fallback_content(
+    session: Session,
+    conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
+    id: i32,
+    fallback: Json<FallbackContentDto>,
+) -> Result<Status, AppError> {
+    if !session.is_admin {
+        return Err(AppError::Unauthorized);
+    }
+
+    let db = conn.into_inner();
+    entity::screen::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(AppError::ScreenNotFound)?;
+
+    if let Some(content_id) = fallback.content {
+        entity::content::Entity::find_by_id(content_id)
+            .filter(entity::content::Column::Screen.eq(id))
+            .filter(entity::content::Column::ArchiveDate.is_null())
+            .one(db)
+            .await?
+            .ok_or(AppError::ContentNotFound)?;
+    }
+
+    entity::screen::ActiveModel {
+        id: Set(id),
+        fallback_content: Set(fallback.content),
+        ..Default::default()
+    }
+    .update(db)
+    .await?;
+    notifier.notify();
+
+    Ok(Status::NoContent)
+}
+
+/// Send a command to every client of the screen. Only admins can send commands.
+#[post("/screen/<id>/command", data = "<command>")]
+pub async fn send_screen_command(
+    session: Session,
+    conn: Connection<'_, Db>,
+    registry: &State<ScreenRegistry>,
+    id: i32,
+    command: Json<ScreenCommandDto>,
+) -> Result<Status, AppError> {
+    if !session.is_admin {
+        return Err(AppError::Unauthorized);
+    }
+
+    let db = conn.into_inner();
+    entity::screen::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(AppError::ScreenNotFound)?;
+
+    registry.commands().send(ScreenCommand {
+        screen: Some(id),
+        command: command.into_inner(),
+    });
+
+    Ok(Status::NoContent)
+}
+
+/// Send a command to the clients of every screen. Only admins can send commands.
+#[post("/screen/command", data = "<command>")]
+pub async fn send_command_to_all_screens(
+    session: Session,
+    registry: &State<ScreenRegistry>,
+    command: Json<ScreenCommandDto>,
+) -> Result<Status, AppError> {
+    if !session.is_admin {
+        return Err(AppError::Unauthorized);
+    }
+
+    registry.commands().send(ScreenCommand {
+        screen: None,
+        command: command.into_inner(),
+    });
+
+    Ok(Status::NoContent)
+}
+
 #[cfg(test)]
 mod tests {
-    use common::dtos::ScreenDto;
+    use common::dtos::{
+        ContentDto, EditScreenDto, FallbackContentDto, ScreenCommandDto, ScreenDto,
+        ScreenLayoutDto, ScreenStatusDto, TransitionDto, TransitionType,
+    };
     use rocket::http::Status;
+    use sea_orm::sqlx::types::chrono::Utc;
+    use sea_orm::{ActiveModelTrait, Set};
+    use sea_orm_rocket::Database;
 
-    use crate::test_utils::TestClient;
+    use crate::{assert_app_error, error::AppError, pool::Db, test_utils::TestClient};
 
     #[test]
     fn create_and_list_screens() {
@@ -81,18 +285,199 @@ mod tests {
                     id: 1,
                     name: "Left".to_string(),
                     position: 0,
+                    transition: TransitionDto::default(),
+                    layout: ScreenLayoutDto::default(),
                 },
                 ScreenDto {
                     id: 2,
                     name: "Center".to_string(),
                     position: 1,
+                    transition: TransitionDto::default(),
+                    layout: ScreenLayoutDto::default(),
                 },
                 ScreenDto {
                     id: 3,
                     name: "Right".to_string(),
                     position: 2,
+                    transition: TransitionDto::default(),
+                    layout: ScreenLayoutDto::default(),
                 }
             ])
         );
     }
+
+    #[test]
+    fn update_screen() {
+        let mut client = TestClient::new();
+        let edit = |transition_type, duration| EditScreenDto {
+            name: "Left".to_string(),
+            position: 0,
+            transition: TransitionDto {
+                transition_type,
+                duration,
+            },
+            layout: ScreenLayoutDto::default(),
+        };
+
+        client.login_as("johndoe", false);
+        let response = client
+            .put("/api/screen/1")
+            .json(&edit(TransitionType::Cut, 0))
+            .dispatch();
+        assert_app_error!(response, AppError::Unauthorized);
+
+        client.login_as("admin", true);
+        let response = client
+            .put("/api/screen/42")
+            .json(&edit(TransitionType::Cut, 0))
+            .dispatch();
+        assert_app_error!(response, AppError::ScreenNotFound);
+
+        for transition_type in [
+            TransitionType::Cut,
+            TransitionType::Crossfade,
+            TransitionType::SlideLeft,
+            TransitionType::SlideUp,
+        ] {
+            let response = client
+                .put("/api/screen/1")
+                .json(&edit(transition_type, 750))
+                .dispatch();
+            assert_eq!(response.status(), Status::NoContent);
+
+            let response = client.get("/api/screen").dispatch();
+            let screens = response.into_json::<Vec<ScreenDto>>().unwrap();
+            assert_eq!(
+                screens[0].transition,
+                TransitionDto {
+                    transition_type,
+                    duration: 750,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn list_screen_statuses() {
+        let mut client = TestClient::new();
+
+        client.login_as("johndoe", false);
+        let response = client.get("/api/screen/status").dispatch();
+        assert_app_error!(response, AppError::Unauthorized);
+
+        client.login_as("admin", true);
+        let response = client.get("/api/screen/status").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_json(),
+            Some(
+                (1..=3)
+                    .map(|screen| ScreenStatusDto {
+                        screen,
+                        connections: vec![],
+                        disconnected_since: None,
+                    })
+                    .collect::<Vec<_>>()
+            )
+        );
+    }
+
+    #[test]
+    fn send_screen_commands() {
+        let mut client = TestClient::new();
+
+        client.login_as("johndoe", false);
+        let response = client
+            .post("/api/screen/1/command")
+            .json(&ScreenCommandDto::Reload)
+            .dispatch();
+        assert_app_error!(response, AppError::Unauthorized);
+
+        client.login_as("admin", true);
+        let response = client
+            .post("/api/screen/1/command")
+            .json(&ScreenCommandDto::JumpToSlide { index: 2 })
+            .dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+
+        let response = client
+            .post("/api/screen/42/command")
+            .json(&ScreenCommandDto::Identify)
+            .dispatch();
+        assert_app_error!(response, AppError::ScreenNotFound);
+
+        let response = client
+            .post("/api/screen/command")
+            .json(&ScreenCommandDto::Identify)
+            .dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+    }
+
+    #[test]
+    fn set_fallback_content() {
+        let mut client = TestClient::new();
+
+        client.login_as("johndoe", false);
+        let response = client
+            .put("/api/screen/1/fallback-content")
+            .json(&FallbackContentDto { content: None })
+            .dispatch();
+        assert_app_error!(response, AppError::Unauthorized);
+
+        client.login_as("admin", true);
+        let response = client
+            .put("/api/screen/1/fallback-content")
+            .json(&FallbackContentDto { content: Some(42) })
+            .dispatch();
+        assert_app_error!(response, AppError::ContentNotFound);
+
+        let response = client
+            .put("/api/screen/42/fallback-content")
+            .json(&FallbackContentDto { content: None })
+            .dispatch();
+        assert_app_error!(response, AppError::ScreenNotFound);
+
+        let response = client
+            .put("/api/screen/1/fallback-content")
+            .json(&FallbackContentDto { content: None })
+            .dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+
+        let response = client.get("/api/screen/1/fallback-content").dispatch();
+        assert_eq!(response.into_json::<Option<ContentDto>>(), Some(None));
+    }
+
+    #[async_test]
+    async fn archived_fallback_content_is_hidden() {
+        let client = rocket::local::asynchronous::Client::tracked(crate::rocket())
+            .await
+            .expect("failed to init rocket client");
+        let db = &Db::fetch(client.rocket()).expect("no database").conn;
+
+        let content = entity::content::ActiveModel {
+            screen: Set(1),
+            content_type: Set(entity::sea_orm_active_enums::ContentType::Image),
+            file_path: Set("archived".to_string()),
+            archive_date: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .expect("failed to insert content");
+        entity::screen::ActiveModel {
+            id: Set(1),
+            fallback_content: Set(Some(content.id)),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+        .expect("failed to update screen");
+
+        let response = client
+            .get("/api/screen/1/fallback-content")
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_json::<Option<ContentDto>>().await, Some(None));
+    }
 }
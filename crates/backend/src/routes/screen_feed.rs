@@ -1,13 +1,23 @@
-use std::env;
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    fmt::Write,
+};
 
-use common::dtos::{AppErrorDto, FeedEntryDto};
-use entity::{sea_orm::entity::prelude::Expr, sea_orm_active_enums::ContentType};
+use chrono_tz::Europe::Stockholm;
+use common::dtos::{
+    FeedDto, FeedEntryDto, FeedPreviewDto, ScreenDto, ScreenFeedEventDto, ScreenFeedPayloadDto,
+    TextContentDto, TransitionDto, UserInfoDto,
+};
+use entity::{
+    sea_orm::entity::prelude::Expr,
+    sea_orm_active_enums::{ContentType, TransitionType},
+};
 use rocket::{
+    http::Status,
     response::stream::{Event, EventStream},
-    tokio::{
-        select,
-        time::{self, Duration},
-    },
+    serde::json::Json,
+    tokio::{select, sync::broadcast::error::RecvError, time::Duration},
     Shutdown, State,
 };
 use sea_orm::{
@@ -15,87 +25,292 @@ use sea_orm::{
     JoinType, QueryFilter, QueryOrder, QuerySelect, RelationTrait,
 };
 use sea_orm_rocket::Connection;
+use sha2::{Digest, Sha256};
 
-use crate::{error::AppError, files::Files, pool::Db};
+use crate::{
+    auth::{hive::HiveClient, Session},
+    error::AppError,
+    feed_cache::FeedCache,
+    files::Files,
+    guards::client_info::ClientInfo,
+    pool::Db,
+    recurrence::Recurrence,
+    rotation::{self, RotationOrder},
+    screen_registry::ScreenRegistry,
+};
 
 const FEED_ENTRY_DURATION: i32 = 10_000;
 
-#[get("/feed/<screen>")]
+/// How often a comment is sent on the event stream while the feed doesn't change, so that the
+/// connection isn't closed by proxies for being idle.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Get the feed of a screen once, for clients which can't use the event stream. If `since` is the
+/// current version of the feed, only `304 Not Modified` is returned.
+#[get("/feed/<screen>?<since>", format = "json")]
+pub async fn get_screen_feed_once(
+    screen: i32,
+    since: Option<&str>,
+    feed_cache: &State<FeedCache>,
+) -> Result<FeedResponse, AppError> {
+    let feed = feed_cache
+        .get(screen)
+        .await?
+        .map_err(|err| AppError::FeedUnavailable(err.msg))?;
+    if since == Some(feed.version.as_str()) {
+        Ok(FeedResponse::NotModified(Status::NotModified))
+    } else {
+        Ok(FeedResponse::Feed(Json(stamp_server_time(feed))))
+    }
+}
+
+#[derive(Responder)]
+pub enum FeedResponse {
+    Feed(Json<FeedDto>),
+    NotModified(Status),
+}
+
+/// Stream the feed of a screen, sending it again whenever it changes. Has a lower rank than
+/// [`get_screen_feed_once`] so that it's only used if the client accepts an event stream.
+///
+/// The current emergency message is sent as a separate event whenever it's raised, cleared or
+/// expires, along with commands from admins and errors. Every event is a
+/// [`ScreenFeedEventDto`], named after its payload.
+#[get("/feed/<screen>", rank = 2)]
 pub async fn get_screen_feed<'a>(
     screen: i32,
-    conn: Connection<'a, Db>,
     mut shutdown: Shutdown,
-    files: &'a State<Files>,
+    feed_cache: &'a State<FeedCache>,
+    registry: &'a State<ScreenRegistry>,
+    client: ClientInfo,
 ) -> EventStream![Event + 'a] {
-    let feed_entry_duration = env::var("FEED_ENTRY_DURATION")
-        .unwrap_or(FEED_ENTRY_DURATION.to_string())
-        .parse::<i32>()
-        .unwrap_or(FEED_ENTRY_DURATION);
-
     EventStream! {
-        let db = conn.into_inner();
-        // TODO use queue/notify instead of interval
-        let mut interval = time::interval(Duration::from_secs(60));
+        // unregistered when the stream is dropped, i.e. when the client disconnects
+        let connection = registry.connect(screen, client.remote_address, client.user_agent);
+        let mut alert = feed_cache.subscribe_alert();
+        let mut feed = feed_cache.subscribe(screen);
+        let mut commands = registry.commands().subscribe();
+        // the current alert and feed are sent right away, so that screens which reconnect learn
+        // whether the emergency message has been cleared while they were disconnected
+        alert.mark_changed();
+        feed.mark_changed();
         loop {
-            select! {
-                _ = interval.tick() => {
-                    match compute_feed(screen, db, files, feed_entry_duration).await {
-                        Ok(data) => yield Event::json(&data),
-                        Err(err) => {
-                            let status = err.status();
-                            if status.code >= 500 {
-                                // debug prints enum variant name, display shows thiserror message
-                                error!("While handling [/feed/{screen}], encountered {err:?}: {err}");
-                            }
-                            yield Event::json(&AppErrorDto::from(err));
-                        }
+            let payload = select! {
+                // the alert is sent first, as it's shown instead of the feed
+                biased;
+                changed = alert.changed() => match changed {
+                    Ok(()) => {
+                        let alert = alert.borrow_and_update().clone();
+                        alert.map(ScreenFeedPayloadDto::Alert)
+                    }
+                    Err(_) => break,
+                },
+                changed = feed.changed() => match changed {
+                    Ok(()) => {
+                        let feed = feed.borrow_and_update().clone();
+                        feed.map(|feed| match feed {
+                            Ok(feed) => ScreenFeedPayloadDto::Feed(feed),
+                            Err(err) => ScreenFeedPayloadDto::FeedError(err),
+                        })
                     }
+                    Err(_) => break,
+                },
+                command = commands.recv() => match command {
+                    Ok(command) if command.targets(screen) => {
+                        Some(ScreenFeedPayloadDto::Command(command.command))
+                    }
+                    Ok(_) => None,
+                    // commands are only sent by hand, so missing some isn't a problem
+                    Err(RecvError::Lagged(_)) => None,
+                    Err(RecvError::Closed) => break,
                 },
                 _ = &mut shutdown => {
                     break;
                 }
             };
+
+            if let Some(payload) = payload {
+                yield feed_event(payload);
+                connection.event_sent();
+            }
         }
     }
+    .heartbeat(KEEP_ALIVE_INTERVAL)
+}
+
+/// Get the feed of every screen as it would be at the given time (in RFC 3339 format), e.g. to
+/// check what will be shown during an event. If `unpublished` is set, unpublished slide groups
+/// owned by the user are included as if they were published. Emergency messages aren't included.
+#[get("/feed/preview?<at>&<unpublished>")]
+pub async fn preview_feeds(
+    session: Session,
+    conn: Connection<'_, Db>,
+    files: &State<Files>,
+    hive_client: &State<HiveClient>,
+    at: &str,
+    unpublished: Option<bool>,
+) -> Result<Json<Vec<FeedPreviewDto>>, AppError> {
+    let at = chrono::DateTime::parse_from_rfc3339(at)
+        .map_err(|_| AppError::InvalidTimestamp)?
+        .to_utc();
+    let user_info = match unpublished {
+        Some(true) => Some(session.populate(hive_client).await?),
+        _ => None,
+    };
+
+    let db = conn.into_inner();
+    let screens = entity::screen::Entity::find()
+        .order_by_asc(entity::screen::Column::Position)
+        .all(db)
+        .await?;
+
+    let feed_entry_duration = feed_entry_duration();
+    let ordering = feed_ordering();
+    let mut previews = Vec::with_capacity(screens.len());
+    for screen in screens {
+        let feed = compute_feed(
+            screen.id,
+            db,
+            files,
+            feed_entry_duration,
+            ordering,
+            at,
+            user_info.as_ref(),
+        )
+        .await?;
+        previews.push(FeedPreviewDto {
+            screen: ScreenDto::from(screen),
+            feed,
+        });
+    }
+
+    Ok(Json(previews))
+}
+
+fn feed_event(payload: ScreenFeedPayloadDto) -> Event {
+    let payload = match payload {
+        ScreenFeedPayloadDto::Feed(feed) => ScreenFeedPayloadDto::Feed(stamp_server_time(feed)),
+        payload => payload,
+    };
+    let name = payload.event_name();
+    Event::json(&ScreenFeedEventDto::new(payload)).event(name)
+}
+
+/// Feeds are stamped with the time they are sent at rather than when they were computed, as cached
+/// feeds can be much older.
+fn stamp_server_time(mut feed: FeedDto) -> FeedDto {
+    feed.server_time = chrono::Utc::now();
+    feed
+}
+
+pub(crate) fn feed_entry_duration() -> i32 {
+    env::var("FEED_ENTRY_DURATION")
+        .unwrap_or(FEED_ENTRY_DURATION.to_string())
+        .parse::<i32>()
+        .unwrap_or(FEED_ENTRY_DURATION)
+}
+
+/// How the slides are ordered in the rotation, from `FEED_ORDERING` (`grouped`, `interleaved` or
+/// `shuffled`), see [`RotationOrder`].
+pub(crate) fn feed_ordering() -> RotationOrder {
+    let Ok(ordering) = env::var("FEED_ORDERING") else {
+        return RotationOrder::default();
+    };
+    ordering.parse().unwrap_or_else(|_| {
+        warn!("unknown FEED_ORDERING {ordering:?}, falling back to grouped");
+        RotationOrder::default()
+    })
 }
 
 #[derive(FromQueryResult)]
 struct PartialEntry {
+    group_id: i32,
     priority: i32,
+    takeover: bool,
+    end_date: Option<chrono::NaiveDateTime>,
+    transition: Option<TransitionType>,
+    transition_duration_ms: Option<i32>,
+    recurrence_weekdays: Option<i32>,
+    recurrence_start_time: Option<chrono::NaiveTime>,
+    recurrence_end_time: Option<chrono::NaiveTime>,
+    slide_id: i32,
+    duration_ms: Option<i32>,
     content_type: Option<ContentType>,
     file_path: Option<String>,
+    rendition_path: Option<String>,
+    markdown: Option<String>,
+    background_color: Option<String>,
+    text_color: Option<String>,
 }
 
-/// Get the slideshow feed for a screen, taking into account the following criteria:
+/// Get the slideshow feed for a screen at the time `now`, taking into account the following
+/// criteria:
 /// - Only non-archived content from non-archived slide groups and slides are considered
-/// - Only published slide groups are considered
-/// - Only groups whose start date is before current time and end date (if any) after current time
-///   are considered
-/// - Of the groups to be considered, only the ones with the maximum priority are included
-/// - Feed entries are sorted by group id first, then position, then slide id, to ensure stable ordering
+/// - Only published slide groups are considered, along with the unpublished ones owned by
+///   `unpublished_of` (all of them for admins) if set
+/// - Only groups whose start date is before `now` and end date (if any) after `now` are considered
+/// - Groups with a weekly recurrence are only considered within its time windows, evaluated in
+///   Swedish time
+/// - Groups restricted to a set of screens are only considered for those screens
+/// - If any of the groups to be considered have takeover enabled, only those groups are included
+/// - Slides within a group are sorted by position, then slide id, to ensure stable ordering
+/// - Groups are rotated in order of their id, with each group appearing as many times as its
+///   weight (based on its priority), see [`rotation::weighted_rotation`]
+/// - With [`RotationOrder::Shuffled`], groups and the slides within them are instead shuffled with
+///   the Swedish date of `now` as seed, see [`rotation::shuffle_key`]
 /// - If a slide is to be shown but does not have content on the given screen, send an image with
 ///   empty url instead (so that the feed is still aligned with other screens)
-/// - For now, slide duration is hardcoded as 30 seconds
+/// - Slides use the transition of their group if set, otherwise the default of the screen
+/// - If no slides are to be shown, the fallback content of the screen is shown instead (if set)
+/// - Slides are shown for their own duration if set, otherwise for the longest video among their
+///   content on the screens the group is shown on (so that those screens stay aligned), otherwise
+///   for `feed_entry_duration`
+///
+/// Screens are only aligned with each other if the same slide groups are shown on them, e.g. if
+/// all groups shown on one are restricted to the same set of screens.
+///
+/// The version of the feed is a hash of its entries, see [`feed_version`].
 ///
 /// Assumptions:
 /// - There is at most one non-archived content per slide
-async fn compute_feed(
+pub(crate) async fn compute_feed(
     screen: i32,
     db: &DatabaseConnection,
     files: &Files,
     feed_entry_duration: i32,
-) -> Result<Vec<FeedEntryDto>, AppError> {
-    let now = chrono::Utc::now();
+    ordering: RotationOrder,
+    now: chrono::DateTime<chrono::Utc>,
+    unpublished_of: Option<&UserInfoDto>,
+) -> Result<FeedDto, AppError> {
+    let published = match unpublished_of {
+        None => Condition::all().add(entity::slide_group::Column::Published.eq(true)),
+        Some(user_info) if user_info.is_admin => Condition::all(),
+        Some(user_info) => Condition::any()
+            .add(entity::slide_group::Column::Published.eq(true))
+            .add(entity::slide_group::Column::CreatedBy.is_in(owner_ids(user_info))),
+    };
 
     let entries: Vec<PartialEntry> = entity::slide::Entity::find()
         .select_only()
         // add relevant columns to select
-        .column(entity::slide_group::Column::Id)
+        .column_as(entity::slide_group::Column::Id, "group_id")
         .column(entity::slide_group::Column::Priority)
+        .column(entity::slide_group::Column::Takeover)
+        .column(entity::slide_group::Column::RecurrenceWeekdays)
+        .column(entity::slide_group::Column::RecurrenceStartTime)
+        .column(entity::slide_group::Column::RecurrenceEndTime)
         .column(entity::slide_group::Column::StartDate)
+        .column(entity::slide_group::Column::EndDate)
+        .column(entity::slide_group::Column::Transition)
+        .column(entity::slide_group::Column::TransitionDurationMs)
         .column(entity::content::Column::ContentType)
-        .column(entity::slide::Column::Id)
+        .column_as(entity::slide::Column::Id, "slide_id")
+        .column(entity::slide::Column::DurationMs)
         .column(entity::content::Column::FilePath)
+        .column(entity::content::Column::RenditionPath)
+        .column(entity::content::Column::Markdown)
+        .column(entity::content::Column::BackgroundColor)
+        .column(entity::content::Column::TextColor)
         // joins
         .inner_join(entity::slide_group::Entity)
         .join(
@@ -113,15 +328,13 @@ async fn compute_feed(
                         .add(Expr::col((right, entity::content::Column::ArchiveDate)).is_null())
                 }),
         )
-        // used for later filtering by only highest priority items
-        .order_by_desc(entity::slide_group::Column::Priority)
         // ensure stable order, and that position is respected
         .order_by_asc(entity::slide_group::Column::Id)
         .order_by_asc(entity::slide::Column::Position)
         .order_by_asc(entity::slide::Column::Id)
         .order_by_asc(entity::content::Column::Id)
         // ensure published
-        .filter(entity::slide_group::Column::Published.eq(true))
+        .filter(published)
         // ensure not hidden
         .filter(entity::slide_group::Column::Hidden.eq(false))
         // ensure not archived
@@ -139,34 +352,386 @@ async fn compute_feed(
         .all(db)
         .await?;
 
-    // TODO use `RANK() OVER (ORDER BY priority)` to only get highest priority items,
-    // which requires putting the above query in a sub-query and some custom queries because
-    // seaquery does not support it: https://github.com/SeaQL/sea-query/issues/601
-    // See also: https://www.postgresql.org/docs/current/tutorial-window.html
-    let max_priority = entries.first().map(|entry| entry.priority).unwrap_or(0);
+    let local_now = now.with_timezone(&Stockholm).naive_local();
+    let entries: Vec<PartialEntry> = entries
+        .into_iter()
+        .filter(|entry| {
+            Recurrence::from_columns(
+                entry.recurrence_weekdays,
+                entry.recurrence_start_time,
+                entry.recurrence_end_time,
+            )
+            .is_none_or(|recurrence| recurrence.is_active_at(local_now))
+        })
+        .collect();
+
+    let group_screens =
+        get_group_screens(db, entries.iter().map(|entry| entry.group_id).collect()).await?;
+    let entries: Vec<PartialEntry> = entries
+        .into_iter()
+        .filter(|entry| {
+            group_screens
+                .get(&entry.group_id)
+                .is_none_or(|screens| screens.contains(&screen))
+        })
+        .collect();
 
-    Ok(entries
+    // NOTE: this can't be done in the query using `RANK() OVER (ORDER BY takeover)`, since takeover
+    // groups outside of their weekly schedule or restricted to other screens mustn't hide the rest
+    let any_takeover = entries.iter().any(|entry| entry.takeover);
+    let mut entries: Vec<PartialEntry> = entries
         .into_iter()
-        .filter(|entry| entry.priority == max_priority)
-        .map(|entry| FeedEntryDto {
+        .filter(|entry| entry.takeover || !any_takeover)
+        .collect();
+
+    if ordering == RotationOrder::Shuffled {
+        let seed = now.with_timezone(&Stockholm).date_naive().to_string();
+        // the slides of a group must stay next to each other, as they are grouped below
+        entries.sort_by_cached_key(|entry| {
+            (
+                rotation::shuffle_key(&seed, entry.group_id),
+                entry.group_id,
+                rotation::shuffle_key(&seed, entry.slide_id),
+            )
+        });
+    }
+
+    let video_durations = get_video_durations(
+        db,
+        entries
+            .iter()
+            .map(|entry| (entry.slide_id, entry.group_id))
+            .collect(),
+        &group_screens,
+    )
+    .await?;
+
+    let screen_model = entity::screen::Entity::find_by_id(screen).one(db).await?;
+    let default_transition = screen_model
+        .as_ref()
+        .map(|screen| TransitionDto {
+            transition_type: screen.transition.into(),
+            duration: screen.transition_duration_ms,
+        })
+        .unwrap_or_default();
+
+    let mut groups: Vec<(usize, Vec<FeedEntryDto>)> = Vec::new();
+    let mut last_group_id = None;
+    for entry in entries {
+        if last_group_id != Some(entry.group_id) {
+            last_group_id = Some(entry.group_id);
+            groups.push((rotation::group_weight(entry.priority), Vec::new()));
+        }
+
+        let feed_entry = FeedEntryDto {
+            // images are shown scaled to the screen if possible, rather than as uploaded
+            url: match (&entry.content_type, entry.rendition_path) {
+                (Some(_), Some(rendition_path)) => files.file_url(&rendition_path),
+                (Some(content_type), None) => {
+                    files.content_url(content_type, &entry.file_path.unwrap_or_default())
+                }
+                (None, _) => String::new(),
+            },
             content_type: entry
                 .content_type
                 .map(|ct| ct.into())
                 .unwrap_or(common::dtos::ContentType::Image),
-            url: files.file_url(&entry.file_path.unwrap_or_default()),
-            duration: feed_entry_duration,
-        })
-        .collect())
+            duration: entry
+                .duration_ms
+                .or_else(|| video_durations.get(&entry.slide_id).copied())
+                .unwrap_or(feed_entry_duration),
+            end_date: entry.end_date.map(|date| date.and_utc()),
+            transition: entry
+                .transition
+                .zip(entry.transition_duration_ms)
+                .map(|(transition_type, duration)| TransitionDto {
+                    transition_type: transition_type.into(),
+                    duration,
+                })
+                .unwrap_or(default_transition),
+            text: TextContentDto::from_columns(
+                entry.markdown,
+                entry.background_color,
+                entry.text_color,
+            ),
+        };
+        if let Some((_, group_entries)) = groups.last_mut() {
+            group_entries.push(feed_entry);
+        }
+    }
+
+    let mut entries = rotation::weighted_rotation(groups, ordering);
+    if entries.is_empty() {
+        let fallback_content = match screen_model.and_then(|screen| screen.fallback_content) {
+            Some(content_id) => {
+                entity::content::Entity::find_by_id(content_id)
+                    .filter(entity::content::Column::ArchiveDate.is_null())
+                    .one(db)
+                    .await?
+            }
+            None => None,
+        };
+        entries.extend(fallback_content.map(|content| FeedEntryDto {
+            url: match &content.rendition_path {
+                Some(rendition_path) => files.file_url(rendition_path),
+                None => files.content_url(&content.content_type, &content.file_path),
+            },
+            content_type: content.content_type.into(),
+            duration: content.duration_ms.unwrap_or(feed_entry_duration),
+            end_date: None,
+            transition: default_transition,
+            text: TextContentDto::from_columns(
+                content.markdown,
+                content.background_color,
+                content.text_color,
+            ),
+        }));
+    }
+
+    Ok(FeedDto {
+        version: feed_version(&entries),
+        entries,
+        server_time: chrono::Utc::now(),
+    })
+}
+
+/// The values of `created_by` of the slide groups owned by the user, i.e. their username and the
+/// groups they are a member of.
+fn owner_ids(user_info: &UserInfoDto) -> Vec<String> {
+    std::iter::once(user_info.username.clone())
+        .chain(
+            user_info
+                .memberships
+                .iter()
+                .map(|membership| membership.as_group()),
+        )
+        .collect()
+}
+
+/// Hash the entries of a feed, so that clients can tell whether it has changed.
+fn feed_version(entries: &[FeedEntryDto]) -> String {
+    let mut hasher = Sha256::new();
+    for entry in entries {
+        // fields are separated by a byte which can't be part of the url, to avoid ambiguity
+        hasher.update(format!("{:?}", entry.content_type));
+        hasher.update([0]);
+        hasher.update(&entry.url);
+        hasher.update([0]);
+        hasher.update(entry.duration.to_be_bytes());
+        hasher.update(
+            entry
+                .end_date
+                .map_or(i64::MIN, |date| date.timestamp_millis())
+                .to_be_bytes(),
+        );
+        hasher.update(format!("{:?}", entry.transition.transition_type));
+        hasher.update(entry.transition.duration.to_be_bytes());
+        if let Some(text) = &entry.text {
+            hasher.update(&text.markdown);
+            hasher.update([0]);
+            hasher.update(&text.background_color);
+            hasher.update(&text.text_color);
+        }
+        // entries are separated by a byte which can't be part of the url or markdown either
+        hasher.update([0xff]);
+    }
+    hasher.finalize().iter().fold("".to_string(), |mut s, b| {
+        write!(s, "{:02x}", b).unwrap();
+        s
+    })
+}
+
+/// Get the screens which each of the given slide groups is restricted to. Groups which are shown on
+/// all screens are omitted.
+async fn get_group_screens(
+    db: &DatabaseConnection,
+    group_ids: HashSet<i32>,
+) -> Result<HashMap<i32, HashSet<i32>>, AppError> {
+    if group_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let rows: Vec<(i32, i32)> = entity::slide_group_screen::Entity::find()
+        .select_only()
+        .column(entity::slide_group_screen::Column::SlideGroup)
+        .column(entity::slide_group_screen::Column::Screen)
+        .filter(entity::slide_group_screen::Column::SlideGroup.is_in(group_ids))
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let mut group_screens: HashMap<i32, HashSet<i32>> = HashMap::new();
+    for (group_id, screen) in rows {
+        group_screens.entry(group_id).or_default().insert(screen);
+    }
+
+    Ok(group_screens)
+}
+
+/// Get the duration of the longest video of each of the given slides (mapped to their group),
+/// considering the non-archived content on all screens the slide's group is shown on. Slides
+/// without any videos of known length are omitted.
+async fn get_video_durations(
+    db: &DatabaseConnection,
+    slide_groups: HashMap<i32, i32>,
+    group_screens: &HashMap<i32, HashSet<i32>>,
+) -> Result<HashMap<i32, i32>, AppError> {
+    if slide_groups.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let videos: Vec<(i32, i32, i32)> = entity::content::Entity::find()
+        .select_only()
+        .column(entity::content::Column::Slide)
+        .column(entity::content::Column::Screen)
+        .column(entity::content::Column::DurationMs)
+        .filter(entity::content::Column::Slide.is_in(slide_groups.keys().copied()))
+        .filter(entity::content::Column::ArchiveDate.is_null())
+        .filter(entity::content::Column::DurationMs.is_not_null())
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let mut durations: HashMap<i32, i32> = HashMap::new();
+    for (slide_id, screen, duration) in videos {
+        let shown_on_screen = slide_groups
+            .get(&slide_id)
+            .and_then(|group_id| group_screens.get(group_id))
+            .is_none_or(|screens| screens.contains(&screen));
+        if shown_on_screen {
+            let longest = durations.entry(slide_id).or_insert(duration);
+            *longest = (*longest).max(duration);
+        }
+    }
+
+    Ok(durations)
 }
 
 #[cfg(test)]
 mod tests {
+    use common::dtos::{
+        AppErrorDto, FeedDto, FeedEntryDto, FeedPreviewDto, ScreenFeedEventDto,
+        ScreenFeedPayloadDto, TextContentDto, TransitionDto, TransitionType,
+    };
     use entity::sea_orm_active_enums::ContentType;
     use migration::MigratorTrait;
+    use rocket::http::{Accept, Status};
     use sea_orm::sqlx::types::chrono::Utc;
     use sea_orm::ActiveValue::Set;
     use sea_orm::{ActiveModelTrait, EntityTrait};
 
+    use rocket::local::asynchronous::Client;
+    use sea_orm::DatabaseConnection;
+    use sea_orm_rocket::Database;
+
+    use super::{compute_feed, feed_version, FEED_ENTRY_DURATION};
+    use crate::{
+        assert_app_error, error::AppError, feed_cache::FeedCache, files::Files, pool::Db,
+        rotation::RotationOrder, test_utils::TestClient,
+    };
+
+    /// Start the app without serving requests, so that its database can be seeded directly and
+    /// feeds computed from it.
+    async fn util_feed_client() -> Client {
+        Client::tracked(crate::rocket())
+            .await
+            .expect("failed to init rocket client")
+    }
+
+    fn util_db(client: &Client) -> &DatabaseConnection {
+        &Db::fetch(client.rocket()).expect("no database").conn
+    }
+
+    /// Insert a published slide group which is shown now, on the given screens (or all of them if
+    /// empty).
+    async fn util_seed_group(db: &DatabaseConnection, screens: &[i32]) -> i32 {
+        let yesterday = Utc::now()
+            .checked_sub_days(::chrono::Days::new(1))
+            .unwrap()
+            .naive_utc();
+        let group = entity::slide_group::ActiveModel {
+            title: Set("Lorem Ipsum".to_string()),
+            priority: Set(0),
+            hidden: Set(false),
+            created_by: Set("johndoe".to_string()),
+            start_date: Set(yesterday),
+            published: Set(true),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .expect("failed to insert slide group");
+
+        for screen in screens {
+            entity::slide_group_screen::ActiveModel {
+                slide_group: Set(group.id),
+                screen: Set(*screen),
+            }
+            .insert(db)
+            .await
+            .expect("failed to insert slide group screen");
+        }
+
+        group.id
+    }
+
+    async fn util_seed_slide(
+        db: &DatabaseConnection,
+        group: i32,
+        position: i32,
+        duration_ms: Option<i32>,
+    ) -> i32 {
+        entity::slide::ActiveModel {
+            position: Set(position),
+            group: Set(group),
+            duration_ms: Set(duration_ms),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .expect("failed to insert slide")
+        .id
+    }
+
+    /// Insert an image, or a video if `duration_ms` is set.
+    async fn util_seed_content(
+        db: &DatabaseConnection,
+        slide: i32,
+        screen: i32,
+        duration_ms: Option<i32>,
+    ) {
+        entity::content::ActiveModel {
+            slide: Set(Some(slide)),
+            screen: Set(screen),
+            content_type: Set(match duration_ms {
+                Some(_) => ContentType::Video,
+                None => ContentType::Image,
+            }),
+            file_path: Set(format!("slide_{slide}_screen_{screen}")),
+            duration_ms: Set(duration_ms),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .expect("failed to insert content");
+    }
+
+    async fn util_feed(client: &Client, screen: i32) -> Vec<FeedEntryDto> {
+        let files = client.rocket().state::<Files>().expect("no files");
+        compute_feed(
+            screen,
+            util_db(client),
+            files,
+            FEED_ENTRY_DURATION,
+            RotationOrder::Grouped,
+            Utc::now(),
+            None,
+        )
+        .await
+        .expect("failed to compute feed")
+        .entries
+    }
+
     #[async_test]
     async fn feed_computation() {
         let db = sea_orm::Database::connect("sqlite::memory:")
@@ -496,6 +1061,240 @@ mod tests {
         }
     }
 
+    #[async_test]
+    async fn slide_duration_overrides_default() {
+        let client = util_feed_client().await;
+        let db = util_db(&client);
+
+        let group = util_seed_group(db, &[]).await;
+        let timed = util_seed_slide(db, group, 0, Some(4_000)).await;
+        let untimed = util_seed_slide(db, group, 1, None).await;
+        util_seed_content(db, timed, 1, None).await;
+        util_seed_content(db, untimed, 1, None).await;
+
+        let durations: Vec<i32> = util_feed(&client, 1)
+            .await
+            .iter()
+            .map(|entry| entry.duration)
+            .collect();
+        assert_eq!(durations, vec![4_000, FEED_ENTRY_DURATION]);
+    }
+
+    #[async_test]
+    async fn group_transition_overrides_screen_default() {
+        let client = util_feed_client().await;
+        let db = util_db(&client);
+
+        entity::screen::ActiveModel {
+            id: Set(1),
+            transition: Set(entity::sea_orm_active_enums::TransitionType::SlideUp),
+            transition_duration_ms: Set(300),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+        .expect("failed to update screen");
+
+        let default = util_seed_group(db, &[]).await;
+        let slide = util_seed_slide(db, default, 0, None).await;
+        util_seed_content(db, slide, 1, None).await;
+        let custom = util_seed_group(db, &[]).await;
+        entity::slide_group::ActiveModel {
+            id: Set(custom),
+            transition: Set(Some(
+                entity::sea_orm_active_enums::TransitionType::Crossfade,
+            )),
+            transition_duration_ms: Set(Some(1_000)),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+        .expect("failed to update slide group");
+        let slide = util_seed_slide(db, custom, 0, None).await;
+        util_seed_content(db, slide, 1, None).await;
+
+        let transitions: Vec<TransitionDto> = util_feed(&client, 1)
+            .await
+            .iter()
+            .map(|entry| entry.transition)
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                TransitionDto {
+                    transition_type: TransitionType::SlideUp,
+                    duration: 300,
+                },
+                TransitionDto {
+                    transition_type: TransitionType::Crossfade,
+                    duration: 1_000,
+                },
+            ]
+        );
+    }
+
+    #[async_test]
+    async fn targeted_groups_only_on_their_screens() {
+        let client = util_feed_client().await;
+        let db = util_db(&client);
+
+        let targeted = util_seed_group(db, &[1]).await;
+        let slide = util_seed_slide(db, targeted, 0, None).await;
+        util_seed_content(db, slide, 1, None).await;
+        util_seed_content(db, slide, 2, None).await;
+        let everywhere = util_seed_group(db, &[]).await;
+        let slide = util_seed_slide(db, everywhere, 0, None).await;
+        util_seed_content(db, slide, 2, None).await;
+
+        let feed = util_feed(&client, 1).await;
+        assert_eq!(feed.len(), 2);
+        assert!(feed[0].url.ends_with("slide_1_screen_1"));
+
+        // content of the targeted group on other screens is ignored
+        let feed = util_feed(&client, 2).await;
+        assert_eq!(feed.len(), 1);
+        assert!(feed[0].url.ends_with("slide_2_screen_2"));
+    }
+
+    #[async_test]
+    async fn targeted_groups_align_among_their_screens() {
+        let client = util_feed_client().await;
+        let db = util_db(&client);
+
+        let group = util_seed_group(db, &[1, 2]).await;
+        let slide = util_seed_slide(db, group, 0, None).await;
+        util_seed_content(db, slide, 1, Some(5_000)).await;
+        // longer, but on a screen which the group isn't shown on
+        util_seed_content(db, slide, 3, Some(20_000)).await;
+
+        let feed = util_feed(&client, 1).await;
+        assert_eq!(feed.len(), 1);
+        assert_eq!(feed[0].duration, 5_000);
+
+        // padded for as long as the video on the other targeted screen
+        let feed = util_feed(&client, 2).await;
+        assert_eq!(feed.len(), 1);
+        assert_eq!(feed[0].url, "");
+        assert_eq!(feed[0].duration, 5_000);
+
+        // not padded, as the group isn't shown there at all
+        assert_eq!(util_feed(&client, 3).await, vec![]);
+    }
+
+    #[test]
+    fn feed_version_changes_with_entries() {
+        let entry = FeedEntryDto {
+            content_type: common::dtos::ContentType::Image,
+            url: "https://example.com/a.png".to_string(),
+            duration: 10_000,
+            end_date: None,
+            transition: TransitionDto::default(),
+            text: None,
+        };
+        let version = feed_version(std::slice::from_ref(&entry));
+
+        assert_eq!(version, feed_version(std::slice::from_ref(&entry)));
+        assert_ne!(version, feed_version(&[]));
+        assert_ne!(
+            version,
+            feed_version(&[FeedEntryDto {
+                duration: 5_000,
+                ..entry.clone()
+            }])
+        );
+        assert_ne!(
+            version,
+            feed_version(&[FeedEntryDto {
+                end_date: Some(Utc::now()),
+                ..entry.clone()
+            }])
+        );
+        assert_ne!(
+            version,
+            feed_version(&[FeedEntryDto {
+                text: Some(TextContentDto::default()),
+                ..entry.clone()
+            }])
+        );
+        assert_ne!(version, feed_version(&[entry.clone(), entry]));
+    }
+
+    #[test]
+    fn feed_event_format() {
+        let event = ScreenFeedEventDto::new(ScreenFeedPayloadDto::FeedError(AppErrorDto {
+            msg: "database error".to_string(),
+        }));
+
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "version": 1,
+                "payload": { "type": "feed-error", "data": { "msg": "database error" } },
+            })
+        );
+        assert_eq!(event.payload.event_name(), "feed-error");
+    }
+
+    #[test]
+    fn preview_feeds() {
+        let mut client = TestClient::new();
+
+        let response = client
+            .get("/api/feed/preview?at=2026-10-16T16:00:00Z")
+            .dispatch();
+        assert_app_error!(response, AppError::Unauthenticated);
+
+        client.login_as("johndoe", false);
+        let response = client.get("/api/feed/preview?at=friday").dispatch();
+        assert_app_error!(response, AppError::InvalidTimestamp);
+
+        let response = client
+            .get("/api/feed/preview?at=2026-10-16T16:00:00Z")
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let previews = response.into_json::<Vec<FeedPreviewDto>>().unwrap();
+        assert_eq!(
+            previews
+                .iter()
+                .map(|preview| preview.screen.id)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[async_test]
+    async fn cached_feed_has_current_server_time() {
+        let client = util_feed_client().await;
+        // keeps the feed cached between the requests
+        let _subscription = client
+            .rocket()
+            .state::<FeedCache>()
+            .expect("no feed cache")
+            .subscribe(1);
+
+        let first = client
+            .get("/api/feed/1")
+            .header(Accept::JSON)
+            .dispatch()
+            .await
+            .into_json::<FeedDto>()
+            .await
+            .expect("no feed");
+        rocket::tokio::time::sleep(std::time::Duration::from_millis(1_100)).await;
+
+        let requested_at = Utc::now();
+        let second = client
+            .get("/api/feed/1")
+            .header(Accept::JSON)
+            .dispatch()
+            .await
+            .into_json::<FeedDto>()
+            .await
+            .expect("no feed");
+        assert_eq!(second.version, first.version);
+        assert!(second.server_time >= requested_at);
+    }
+
     // TODO test the eventstream when proper signaling is implemented
     // Useful reference: https://github.com/rwf2/Rocket/blob/v0.5.1/examples/chat/src/tests.rs#L33
 }
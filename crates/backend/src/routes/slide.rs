@@ -6,6 +6,7 @@ use sea_orm_rocket::Connection;
 use crate::{
     auth::{hive::HiveClient, Session},
     error::AppError,
+    notifier::FeedNotifier,
     pool::Db,
     routes::slide_group,
 };
@@ -17,6 +18,7 @@ pub async fn create_slide(
     session: Session,
     hive_client: &State<HiveClient>,
     conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
     slide: Json<CreateSlideDto>,
 ) -> Result<CreatedResponse, AppError> {
     let db = conn.into_inner();
@@ -38,6 +40,7 @@ pub async fn create_slide(
     .await?;
 
     txn.commit().await?;
+    notifier.notify();
 
     // NOTE: non-existent route
     Ok(build_created_response("/api/slide", res.id))
@@ -48,6 +51,7 @@ pub async fn bulk_move_slides(
     session: Session,
     hive_client: &State<HiveClient>,
     conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
     positions: Json<MoveSlidesDto>,
 ) -> Result<Status, AppError> {
     let db = conn.into_inner();
@@ -79,6 +83,7 @@ pub async fn bulk_move_slides(
     }
 
     txn.commit().await?;
+    notifier.notify();
 
     Ok(Status::NoContent)
 }
@@ -88,6 +93,7 @@ pub async fn delete_slide(
     session: Session,
     hive_client: &State<HiveClient>,
     conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
     id: i32,
 ) -> Result<Status, AppError> {
     let db = conn.into_inner();
@@ -120,6 +126,7 @@ pub async fn delete_slide(
     .await?;
 
     txn.commit().await?;
+    notifier.notify();
 
     Ok(Status::NoContent)
 }
@@ -151,10 +158,14 @@ mod tests {
                 id: 1,
                 title: "Lorem Ipsum".to_string(),
                 priority: 0,
+                takeover: false,
                 hidden: false,
                 created_by: OwnerDto::User("johndoe".to_string()),
                 start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
                 end_date: None,
+                recurrence: None,
+                transition: None,
+                screens: vec![],
                 archive_date: None,
                 published: false,
                 slides: vec![
@@ -162,18 +173,21 @@ mod tests {
                         id: 1,
                         position: 1,
                         archive_date: None,
+                        duration: None,
                         content: vec![],
                     },
                     SlideDto {
                         id: 2,
                         position: 2,
                         archive_date: None,
+                        duration: None,
                         content: vec![],
                     },
                     SlideDto {
                         id: 3,
                         position: 3,
                         archive_date: None,
+                        duration: None,
                         content: vec![],
                     }
                 ],
@@ -209,10 +223,14 @@ mod tests {
                 id: 1,
                 title: "Lorem Ipsum".to_string(),
                 priority: 0,
+                takeover: false,
                 hidden: false,
                 created_by: OwnerDto::User("johndoe".to_string()),
                 start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
                 end_date: None,
+                recurrence: None,
+                transition: None,
+                screens: vec![],
                 archive_date: None,
                 published: false,
                 slides: vec![
@@ -220,18 +238,21 @@ mod tests {
                         id: 2,
                         position: 3,
                         archive_date: None,
+                        duration: None,
                         content: vec![],
                     },
                     SlideDto {
                         id: 3,
                         position: 3,
                         archive_date: None,
+                        duration: None,
                         content: vec![],
                     },
                     SlideDto {
                         id: 1,
                         position: 5,
                         archive_date: None,
+                        duration: None,
                         content: vec![],
                     },
                 ],
@@ -1,11 +1,11 @@
 use common::dtos::{
     ContentDto, CreateSlideGroupDto, EditSlideDto, EditSlideGroupDto, GroupDto, LangDto, OwnerDto,
-    SlideDto, SlideGroupDto, UserInfoDto,
+    SlideDto, SlideGroupDto, TransitionDto, UserInfoDto,
 };
 use rocket::{http::Status, serde::json::Json, State};
 use sea_orm::{
     sqlx::types::chrono, ActiveModelTrait, ColumnTrait, DatabaseTransaction, EntityTrait,
-    QueryFilter, QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait,
 };
 use sea_orm_rocket::Connection;
 use std::collections::{HashMap, HashSet};
@@ -14,8 +14,10 @@ use crate::{
     auth::{hive::HiveClient, Session},
     error::AppError,
     files::Files,
+    notifier::FeedNotifier,
     pool::Db,
-    routes::Lang,
+    recurrence::Recurrence,
+    routes::{content::FromContentModel, Lang},
 };
 
 use super::{build_created_response, CreatedResponse};
@@ -141,14 +143,37 @@ async fn get_slide_group_dto(
         .all(txn)
         .await?;
 
+    let screens = entity::slide_group_screen::Entity::find()
+        .select_only()
+        .column(entity::slide_group_screen::Column::Screen)
+        .filter(entity::slide_group_screen::Column::SlideGroup.eq(group.id))
+        .order_by_asc(entity::slide_group_screen::Column::Screen)
+        .into_tuple::<i32>()
+        .all(txn)
+        .await?;
+
     Ok(SlideGroupDto {
         id: group.id,
         title: group.title,
         priority: group.priority,
+        takeover: group.takeover,
         hidden: group.hidden,
         created_by: resolve_owner(group.created_by, lang, hive_client).await?,
         start_date: group.start_date.and_utc(),
         end_date: group.end_date.map(|d| d.and_utc()),
+        recurrence: Recurrence::from_columns(
+            group.recurrence_weekdays,
+            group.recurrence_start_time,
+            group.recurrence_end_time,
+        )
+        .map(Recurrence::to_dto),
+        transition: group.transition.zip(group.transition_duration_ms).map(
+            |(transition_type, duration)| TransitionDto {
+                transition_type: transition_type.into(),
+                duration,
+            },
+        ),
+        screens,
         archive_date: group.archive_date.map(|d| d.and_utc()),
         published: group.published,
         slides: slides
@@ -157,15 +182,10 @@ async fn get_slide_group_dto(
                 id: slide.id,
                 position: slide.position,
                 archive_date: slide.archive_date.map(|d| d.and_utc()),
+                duration: slide.duration_ms,
                 content: content
                     .into_iter()
-                    .map(|content| ContentDto {
-                        id: content.id,
-                        screen: content.screen,
-                        content_type: content.content_type.into(),
-                        url: files.file_url(&content.file_path),
-                        archive_date: content.archive_date.map(|d| d.and_utc()),
-                    })
+                    .map(|content| ContentDto::from_model(files, content))
                     .collect(),
             })
             .collect(),
@@ -179,26 +199,48 @@ pub async fn create_slide_group(
     slide_group: Json<CreateSlideGroupDto>,
 ) -> Result<CreatedResponse, AppError> {
     let db = conn.into_inner();
+    let txn = db.begin().await?;
 
     let owner = match &slide_group.owner {
         None => OwnerDto::User(session.username),
         Some(group) => OwnerDto::Group(group.clone()),
     };
 
+    let recurrence = match &slide_group.recurrence {
+        Some(recurrence) => {
+            Some(Recurrence::from_dto(recurrence).ok_or(AppError::InvalidRecurrence)?)
+        }
+        None => None,
+    };
+
     let group = entity::slide_group::ActiveModel {
         title: Set(slide_group.title.clone()),
         priority: Set(slide_group.priority),
+        takeover: Set(slide_group.takeover),
         hidden: Set(slide_group.hidden),
         created_by: Set(owner.id()),
         start_date: Set(slide_group.start_date.naive_utc()),
         end_date: Set(slide_group.end_date.as_ref().map(|d| d.naive_utc())),
+        recurrence_weekdays: Set(recurrence.map(|r| r.weekdays)),
+        recurrence_start_time: Set(recurrence.map(|r| r.start_time)),
+        recurrence_end_time: Set(recurrence.map(|r| r.end_time)),
+        transition: Set(slide_group
+            .transition
+            .map(|transition| transition.transition_type.into())),
+        transition_duration_ms: Set(slide_group
+            .transition
+            .map(|transition| transition.duration.max(0))),
         archive_date: Set(None),
         published: Set(false),
         ..Default::default()
     }
-    .insert(db)
+    .insert(&txn)
     .await?;
 
+    set_slide_group_screens(group.id, &slide_group.screens, &txn).await?;
+
+    txn.commit().await?;
+
     Ok(build_created_response("/api/slide-group", group.id))
 }
 
@@ -206,6 +248,7 @@ pub async fn create_slide_group(
 pub async fn update_slide_group(
     session: Session,
     conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
     id: i32,
     hive_client: &State<HiveClient>,
     slide_group: Json<EditSlideGroupDto>,
@@ -217,33 +260,66 @@ pub async fn update_slide_group(
     check_slide_group_ownership(&session.populate(hive_client).await?, &txn, id).await?;
     get_non_archived_slide_group(id, &txn).await?;
 
+    let recurrence = match &slide_group.recurrence {
+        Some(recurrence) => {
+            Some(Recurrence::from_dto(recurrence).ok_or(AppError::InvalidRecurrence)?)
+        }
+        None => None,
+    };
+
+    // a slide shown for no time at all would break the rotation on the screens
+    let has_invalid_duration = slide_group.slides.iter().any(|slide| match slide {
+        EditSlideDto::Existing { duration, .. } | EditSlideDto::New { duration, .. } => {
+            duration.is_some_and(|duration| duration <= 0)
+        }
+    });
+    if has_invalid_duration {
+        return Err(AppError::InvalidSlideDuration);
+    }
+
     entity::slide_group::ActiveModel {
         id: Set(id),
         title: Set(slide_group.title),
         priority: Set(slide_group.priority),
+        takeover: Set(slide_group.takeover),
         hidden: Set(slide_group.hidden),
         start_date: Set(slide_group.start_date.naive_utc()),
         end_date: Set(slide_group.end_date.as_ref().map(|d| d.naive_utc())),
+        recurrence_weekdays: Set(recurrence.map(|r| r.weekdays)),
+        recurrence_start_time: Set(recurrence.map(|r| r.start_time)),
+        recurrence_end_time: Set(recurrence.map(|r| r.end_time)),
+        transition: Set(slide_group
+            .transition
+            .map(|transition| transition.transition_type.into())),
+        transition_duration_ms: Set(slide_group
+            .transition
+            .map(|transition| transition.duration.max(0))),
         published: Set(slide_group.published),
         ..Default::default()
     }
     .update(&txn)
     .await?;
 
-    let existing_slides: Vec<(i32, i32)> = entity::slide::Entity::find()
+    set_slide_group_screens(id, &slide_group.screens, &txn).await?;
+
+    let existing_slides: Vec<(i32, i32, Option<i32>)> = entity::slide::Entity::find()
         .filter(entity::slide::Column::Group.eq(id))
         .filter(entity::slide::Column::ArchiveDate.is_null())
         .select_only()
         .column(entity::slide::Column::Id)
         .column(entity::slide::Column::Position)
+        .column(entity::slide::Column::DurationMs)
         .into_tuple()
         .all(&txn)
         .await?;
     let existing_slide_ids: HashSet<i32> = existing_slides
         .iter()
-        .map(|(slide_id, _)| *slide_id)
+        .map(|(slide_id, _, _)| *slide_id)
+        .collect();
+    let existing_slide_properties: HashMap<i32, (i32, Option<i32>)> = existing_slides
+        .into_iter()
+        .map(|(slide_id, position, duration)| (slide_id, (position, duration)))
         .collect();
-    let existing_slide_positions: HashMap<i32, i32> = existing_slides.into_iter().collect();
     let mut referenced_slide_ids: HashSet<i32> = HashSet::new();
     let mut content_assignments: HashMap<i32, i32> = HashMap::new();
 
@@ -252,6 +328,7 @@ pub async fn update_slide_group(
             EditSlideDto::Existing {
                 id: slide_id,
                 position,
+                duration,
                 content,
                 ..
             } => {
@@ -259,10 +336,11 @@ pub async fn update_slide_group(
                     return Err(AppError::SlideNotFound);
                 }
 
-                if existing_slide_positions.get(&slide_id).copied() != Some(position) {
+                if existing_slide_properties.get(&slide_id).copied() != Some((position, duration)) {
                     entity::slide::ActiveModel {
                         id: Set(slide_id),
                         position: Set(position),
+                        duration_ms: Set(duration),
                         ..Default::default()
                     }
                     .update(&txn)
@@ -274,10 +352,15 @@ pub async fn update_slide_group(
                     content_assignments.insert(content_id, slide_id);
                 }
             }
-            EditSlideDto::New { position, content } => {
+            EditSlideDto::New {
+                position,
+                duration,
+                content,
+            } => {
                 let inserted = entity::slide::ActiveModel {
                     position: Set(position),
                     group: Set(id),
+                    duration_ms: Set(duration),
                     archive_date: Set(None),
                     ..Default::default()
                 }
@@ -360,6 +443,7 @@ pub async fn update_slide_group(
     }
 
     txn.commit().await?;
+    notifier.notify();
 
     Ok(Status::NoContent)
 }
@@ -368,6 +452,7 @@ pub async fn update_slide_group(
 pub async fn archive_slide_group(
     session: Session,
     conn: Connection<'_, Db>,
+    notifier: &State<FeedNotifier>,
     hive_client: &State<HiveClient>,
     id: i32,
 ) -> Result<Status, AppError> {
@@ -388,10 +473,46 @@ pub async fn archive_slide_group(
     .await?;
 
     txn.commit().await?;
+    notifier.notify();
 
     Ok(Status::NoContent)
 }
 
+/// Replaces the screens which the slide group is shown on. An empty list means all screens.
+async fn set_slide_group_screens(
+    id: i32,
+    screens: &[i32],
+    txn: &DatabaseTransaction,
+) -> Result<(), AppError> {
+    let screens: HashSet<i32> = screens.iter().copied().collect();
+
+    let existing_screens = entity::screen::Entity::find()
+        .filter(entity::screen::Column::Id.is_in(screens.iter().copied()))
+        .count(txn)
+        .await?;
+    if existing_screens != screens.len() as u64 {
+        return Err(AppError::ScreenNotFound);
+    }
+
+    entity::slide_group_screen::Entity::delete_many()
+        .filter(entity::slide_group_screen::Column::SlideGroup.eq(id))
+        .exec(txn)
+        .await?;
+
+    if !screens.is_empty() {
+        entity::slide_group_screen::Entity::insert_many(screens.into_iter().map(|screen| {
+            entity::slide_group_screen::ActiveModel {
+                slide_group: Set(id),
+                screen: Set(screen),
+            }
+        }))
+        .exec(txn)
+        .await?;
+    }
+
+    Ok(())
+}
+
 async fn get_non_archived_slide_group(
     id: i32,
     txn: &DatabaseTransaction,
@@ -439,7 +560,9 @@ async fn resolve_owner(
 
 #[cfg(test)]
 mod tests {
-    use common::dtos::{EditSlideGroupDto, OwnerDto, SlideGroupDto};
+    use common::dtos::{
+        CreateSlideGroupDto, EditSlideDto, EditSlideGroupDto, OwnerDto, SlideGroupDto,
+    };
     use rocket::http::Status;
     use sea_orm::prelude::DateTimeUtc;
 
@@ -464,10 +587,14 @@ mod tests {
                 id: 1,
                 title: "Lorem Ipsum".to_string(),
                 priority: 0,
+                takeover: false,
                 hidden: false,
                 created_by: OwnerDto::User("johndoe".to_string()),
                 start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
                 end_date: None,
+                recurrence: None,
+                transition: None,
+                screens: vec![],
                 archive_date: None,
                 published: false,
                 slides: vec![],
@@ -492,10 +619,14 @@ mod tests {
                 id: 1,
                 title: "Lorem Ipsum".to_string(),
                 priority: 0,
+                takeover: false,
                 hidden: false,
                 created_by: OwnerDto::User("johndoe".to_string()),
                 start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
                 end_date: None,
+                recurrence: None,
+                transition: None,
+                screens: vec![],
                 archive_date: None,
                 published: false,
                 slides: vec![],
@@ -503,6 +634,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn create_slide_group_on_unknown_screen() {
+        let mut client = TestClient::new();
+        client.login_as("johndoe", false);
+
+        let response = client
+            .post("/api/slide-group")
+            .json(&CreateSlideGroupDto {
+                title: "Lorem Ipsum".to_string(),
+                owner: None,
+                priority: 0,
+                takeover: false,
+                hidden: false,
+                start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
+                end_date: None,
+                recurrence: None,
+                transition: None,
+                screens: vec![1, 42],
+            })
+            .dispatch();
+        assert_app_error!(response, AppError::ScreenNotFound);
+    }
+
     #[test]
     fn get_slide_group_not_found() {
         let client = TestClient::new();
@@ -526,10 +680,14 @@ mod tests {
                 id: 1,
                 title: "Lorem Ipsum".to_string(),
                 priority: 1,
+                takeover: false,
                 hidden: false,
                 created_by: OwnerDto::User("johndoe".to_string()),
                 start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
                 end_date: Some(DateTimeUtc::from_timestamp_nanos(1739471975000000)),
+                recurrence: None,
+                transition: None,
+                screens: vec![],
                 archive_date: None,
                 published: false,
                 slides: vec![],
@@ -546,10 +704,14 @@ mod tests {
                 id: 1,
                 title: "Lorem Ipsum".to_string(),
                 priority: 1,
+                takeover: false,
                 hidden: false,
                 created_by: OwnerDto::User("johndoe".to_string()),
                 start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
                 end_date: Some(DateTimeUtc::from_timestamp_nanos(1739471975000000)),
+                recurrence: None,
+                transition: None,
+                screens: vec![],
                 archive_date: None,
                 published: false,
                 slides: vec![],
@@ -568,10 +730,14 @@ mod tests {
                 id: 1,
                 title: "Lorem Ipsum".to_string(),
                 priority: 1,
+                takeover: false,
                 hidden: false,
                 created_by: OwnerDto::User("johndoe".to_string()),
                 start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
                 end_date: Some(DateTimeUtc::from_timestamp_nanos(1739471975000000)),
+                recurrence: None,
+                transition: None,
+                screens: vec![],
                 archive_date: None,
                 published: false,
                 slides: vec![],
@@ -597,10 +763,14 @@ mod tests {
                 id: 1,
                 title: "Lorem Ipsum".to_string(),
                 priority: 1,
+                takeover: false,
                 hidden: false,
                 created_by: OwnerDto::User("johndoe".to_string()),
                 start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
                 end_date: Some(DateTimeUtc::from_timestamp_nanos(1739471975000000)),
+                recurrence: None,
+                transition: None,
+                screens: vec![],
                 archive_date: None,
                 published: false,
                 slides: vec![],
@@ -609,6 +779,39 @@ mod tests {
         assert_app_error!(response, AppError::SlideGroupArchived);
     }
 
+    #[test]
+    fn update_slide_group_invalid_duration() {
+        let mut client = TestClient::new();
+        client.login_as("johndoe", false);
+
+        util_create_slide_group(&client);
+
+        let response = client
+            .put("/api/slide-group/1")
+            .json(&EditSlideGroupDto {
+                id: 1,
+                title: "Lorem Ipsum".to_string(),
+                priority: 0,
+                takeover: false,
+                hidden: false,
+                created_by: OwnerDto::User("johndoe".to_string()),
+                start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
+                end_date: None,
+                recurrence: None,
+                transition: None,
+                screens: vec![],
+                archive_date: None,
+                published: false,
+                slides: vec![EditSlideDto::New {
+                    position: 0,
+                    duration: Some(0),
+                    content: vec![],
+                }],
+            })
+            .dispatch();
+        assert_app_error!(response, AppError::InvalidSlideDuration);
+    }
+
     #[test]
     fn archive_slide_group() {
         let mut client = TestClient::new();
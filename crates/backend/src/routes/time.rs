@@ -0,0 +1,29 @@
+use common::dtos::ServerTimeDto;
+use rocket::serde::json::Json;
+use sea_orm::sqlx::types::chrono::Utc;
+
+/// Used by screens to measure the offset between their clock and the server's.
+#[get("/time")]
+pub fn server_time() -> Json<ServerTimeDto> {
+    Json(ServerTimeDto { time: Utc::now() })
+}
+
+#[cfg(test)]
+mod tests {
+    use common::dtos::ServerTimeDto;
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+    use sea_orm::sqlx::types::chrono::Utc;
+
+    #[test]
+    fn server_time() {
+        let client = Client::tracked(crate::rocket()).unwrap();
+        let before = Utc::now();
+        let response = client.get("/api/time").dispatch();
+        let after = Utc::now();
+
+        assert_eq!(response.status(), Status::Ok);
+        let time = response.into_json::<ServerTimeDto>().unwrap().time;
+        assert!(before <= time && time <= after);
+    }
+}
@@ -7,9 +7,13 @@ use entity::slide_group;
 use rocket::{tokio, Orbit, Rocket};
 use sea_orm::{ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
 use sea_orm_rocket::Database;
-use std::{fmt::Display, future::Future, time::Duration};
+use std::{
+    fmt::Display,
+    future::{self, Future},
+    time::Duration,
+};
 
-use crate::pool::Db;
+use crate::{notifier::FeedNotifier, pool::Db, screen_registry::ScreenRegistry};
 
 /// Handle result from async function, logging the error if it has failed without panicing.
 async fn log_job_error<O, E, R>(result: R)
@@ -22,23 +26,36 @@ where
     }
 }
 
-pub async fn unpin_slide_groups(db: DatabaseConnection) -> Result<(), DbErr> {
+/// Disable takeover for all slide groups, so that pinned slide groups don't hide everything else
+/// indefinitely.
+pub async fn unpin_slide_groups(
+    db: DatabaseConnection,
+    notifier: FeedNotifier,
+) -> Result<(), DbErr> {
     println!("Unpinning slides groups");
 
-    slide_group::Entity::update_many()
+    let result = slide_group::Entity::update_many()
         .set(slide_group::ActiveModel {
-            priority: ActiveValue::Set(0),
+            takeover: ActiveValue::Set(false),
             ..Default::default()
         })
         .filter(slide_group::Column::ArchiveDate.is_null())
+        .filter(slide_group::Column::Takeover.eq(true))
         .exec(&db)
         .await?;
 
+    if result.rows_affected > 0 {
+        notifier.notify();
+    }
+
     Ok(())
 }
 
 /// Archive all slide groups with an end date which expired at least a day ago.
-pub async fn archive_expired_slide_groups(db: DatabaseConnection) -> Result<(), DbErr> {
+pub async fn archive_expired_slide_groups(
+    db: DatabaseConnection,
+    notifier: FeedNotifier,
+) -> Result<(), DbErr> {
     let now = Utc::now().naive_utc();
     let cutoff_date = now - chrono::Duration::days(1);
 
@@ -54,11 +71,26 @@ pub async fn archive_expired_slide_groups(db: DatabaseConnection) -> Result<(),
 
     if result.rows_affected > 0 {
         println!("Archived {} expired slide groups", result.rows_affected);
+        notifier.notify();
     }
 
     Ok(())
 }
 
+/// Screens are only reported as disconnected after this long, so that reconnects after e.g. a
+/// restart of the server or the screen aren't logged.
+const DISCONNECTION_LOG_THRESHOLD: chrono::Duration = chrono::Duration::minutes(5);
+
+/// Log screens which have been disconnected from their feed for too long.
+pub fn log_long_disconnections(registry: &ScreenRegistry) {
+    for (screen, since) in registry.take_long_disconnections(DISCONNECTION_LOG_THRESHOLD) {
+        println!(
+            "Screen {screen} has been disconnected since {}",
+            since.with_timezone(&Stockholm).format("%Y-%m-%d %H:%M:%S")
+        );
+    }
+}
+
 pub async fn start(rocket: &Rocket<Orbit>) {
     let mut scheduler = AsyncScheduler::with_tz(Stockholm);
 
@@ -66,17 +98,37 @@ pub async fn start(rocket: &Rocket<Orbit>) {
         .expect("Rocket is in orbit phase")
         .conn
         .clone();
+    let notifier = rocket
+        .state::<FeedNotifier>()
+        .expect("Feed notifier is managed")
+        .clone();
 
     let cloned_db = db.clone();
-    scheduler
-        .every(1.days())
-        .at("03:00")
-        .run(move || log_job_error(unpin_slide_groups(cloned_db.clone())));
+    let cloned_notifier = notifier.clone();
+    scheduler.every(1.days()).at("03:00").run(move || {
+        log_job_error(unpin_slide_groups(
+            cloned_db.clone(),
+            cloned_notifier.clone(),
+        ))
+    });
 
     let cloned_db = db.clone();
-    scheduler
-        .every(1.minutes())
-        .run(move || log_job_error(archive_expired_slide_groups(cloned_db.clone())));
+    let cloned_notifier = notifier.clone();
+    scheduler.every(1.minutes()).run(move || {
+        log_job_error(archive_expired_slide_groups(
+            cloned_db.clone(),
+            cloned_notifier.clone(),
+        ))
+    });
+
+    let registry = rocket
+        .state::<ScreenRegistry>()
+        .expect("Screen registry is managed")
+        .clone();
+    scheduler.every(1.minutes()).run(move || {
+        log_long_disconnections(&registry);
+        future::ready(())
+    });
 
     tokio::spawn(async move {
         loop {
@@ -0,0 +1,88 @@
+//! Broadcasts remote maintenance commands from admins to the clients connected to the feeds of the
+//! screens.
+
+use common::dtos::ScreenCommandDto;
+use rocket::tokio::sync::broadcast::{self, Receiver, Sender};
+
+/// Commands are sent by hand, so there is never more than a few in flight.
+const CHANNEL_CAPACITY: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenCommand {
+    /// The screen whose clients should run the command, or `None` for every screen.
+    pub screen: Option<i32>,
+    pub command: ScreenCommandDto,
+}
+
+impl ScreenCommand {
+    pub fn targets(&self, screen: i32) -> bool {
+        self.screen.is_none_or(|target| target == screen)
+    }
+}
+
+#[derive(Clone)]
+pub struct ScreenCommands {
+    sender: Sender<ScreenCommand>,
+}
+
+impl ScreenCommands {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Send a command to every subscriber, returning how many there are.
+    pub fn send(&self, command: ScreenCommand) -> usize {
+        // sending only fails if there are no subscribers, i.e. no screens connected
+        self.sender.send(command).unwrap_or(0)
+    }
+
+    pub fn subscribe(&self) -> Receiver<ScreenCommand> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for ScreenCommands {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::dtos::ScreenCommandDto;
+
+    use super::{ScreenCommand, ScreenCommands};
+
+    #[test]
+    fn send_to_subscribers() {
+        let commands = ScreenCommands::new();
+        let command = ScreenCommand {
+            screen: Some(2),
+            command: ScreenCommandDto::Reload,
+        };
+
+        assert_eq!(commands.send(command.clone()), 0);
+
+        let mut receiver = commands.subscribe();
+        assert_eq!(commands.send(command.clone()), 1);
+        assert_eq!(receiver.try_recv(), Ok(command));
+    }
+
+    #[test]
+    fn command_targets() {
+        let command = ScreenCommand {
+            screen: Some(2),
+            command: ScreenCommandDto::Identify,
+        };
+        assert!(command.targets(2));
+        assert!(!command.targets(1));
+
+        let command = ScreenCommand {
+            screen: None,
+            command: ScreenCommandDto::Identify,
+        };
+        assert!(command.targets(1));
+        assert!(command.targets(2));
+    }
+}
@@ -0,0 +1,236 @@
+//! Keeps track of the clients connected to the feed stream of each screen, so that it's possible
+//! to tell which screens are actually online and to send them commands.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::screen_commands::ScreenCommands;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenConnection {
+    pub screen: i32,
+    pub remote_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub connected_since: DateTime<Utc>,
+    pub last_event: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+struct RegistryState {
+    next_id: u64,
+    connections: HashMap<u64, ScreenConnection>,
+    /// When the last client of each screen disconnected, for screens without any clients.
+    disconnected_since: HashMap<i32, Disconnection>,
+}
+
+struct Disconnection {
+    since: DateTime<Utc>,
+    /// Whether the disconnection has been logged for lasting too long.
+    reported: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct ScreenRegistry {
+    state: Arc<Mutex<RegistryState>>,
+    commands: ScreenCommands,
+}
+
+impl ScreenRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a client connected to the feed of a screen. The client is unregistered when the
+    /// returned handle is dropped, i.e. when its event stream is closed.
+    pub fn connect(
+        &self,
+        screen: i32,
+        remote_address: Option<String>,
+        user_agent: Option<String>,
+    ) -> ConnectionHandle {
+        let now = Utc::now();
+        let mut state = self.state.lock().expect("screen registry lock is poisoned");
+
+        let reported_disconnection = state
+            .disconnected_since
+            .remove(&screen)
+            .filter(|disconnection| disconnection.reported);
+        if let Some(disconnection) = reported_disconnection {
+            info!(
+                "Screen {screen} reconnected after being disconnected for {} minutes",
+                (now - disconnection.since).num_minutes()
+            );
+        }
+
+        let id = state.next_id;
+        state.next_id += 1;
+        state.connections.insert(
+            id,
+            ScreenConnection {
+                screen,
+                remote_address,
+                user_agent,
+                connected_since: now,
+                last_event: None,
+            },
+        );
+
+        ConnectionHandle {
+            registry: self.clone(),
+            id,
+        }
+    }
+
+    /// Commands to be sent to the connected clients.
+    pub fn commands(&self) -> &ScreenCommands {
+        &self.commands
+    }
+
+    /// All connected clients, ordered by screen and then by when they connected.
+    pub fn connections(&self) -> Vec<ScreenConnection> {
+        let state = self.state.lock().expect("screen registry lock is poisoned");
+        // ids are assigned in the order that clients connect
+        let mut connections: Vec<_> = state.connections.iter().collect();
+        connections.sort_by_key(|(id, connection)| (connection.screen, **id));
+        connections
+            .into_iter()
+            .map(|(_, connection)| connection.clone())
+            .collect()
+    }
+
+    /// When the last client of the screen disconnected, if it has no clients. Is `None` for
+    /// screens which haven't had any clients since the server started.
+    pub fn disconnected_since(&self, screen: i32) -> Option<DateTime<Utc>> {
+        let state = self.state.lock().expect("screen registry lock is poisoned");
+        state
+            .disconnected_since
+            .get(&screen)
+            .map(|disconnection| disconnection.since)
+    }
+
+    /// Screens which have been disconnected for longer than `threshold`, along with when they
+    /// disconnected. Each disconnection is only returned once.
+    pub fn take_long_disconnections(&self, threshold: TimeDelta) -> Vec<(i32, DateTime<Utc>)> {
+        let now = Utc::now();
+        let mut state = self.state.lock().expect("screen registry lock is poisoned");
+
+        let mut disconnections: Vec<_> = state
+            .disconnected_since
+            .iter_mut()
+            .filter(|(_, disconnection)| !disconnection.reported)
+            .filter(|(_, disconnection)| now - disconnection.since >= threshold)
+            .map(|(screen, disconnection)| {
+                disconnection.reported = true;
+                (*screen, disconnection.since)
+            })
+            .collect();
+        disconnections.sort();
+        disconnections
+    }
+
+    fn event_sent(&self, id: u64) {
+        let mut state = self.state.lock().expect("screen registry lock is poisoned");
+        if let Some(connection) = state.connections.get_mut(&id) {
+            connection.last_event = Some(Utc::now());
+        }
+    }
+
+    fn disconnect(&self, id: u64) {
+        let mut state = self.state.lock().expect("screen registry lock is poisoned");
+        let Some(connection) = state.connections.remove(&id) else {
+            return;
+        };
+
+        let screen = connection.screen;
+        let has_other_clients = state
+            .connections
+            .values()
+            .any(|connection| connection.screen == screen);
+        if !has_other_clients {
+            state.disconnected_since.insert(
+                screen,
+                Disconnection {
+                    since: Utc::now(),
+                    reported: false,
+                },
+            );
+        }
+    }
+}
+
+/// Registration of a connected client, which is removed from the registry when dropped.
+pub struct ConnectionHandle {
+    registry: ScreenRegistry,
+    id: u64,
+}
+
+impl ConnectionHandle {
+    /// Record that an event has been sent to the client.
+    pub fn event_sent(&self) {
+        self.registry.event_sent(self.id);
+    }
+}
+
+impl Drop for ConnectionHandle {
+    fn drop(&mut self) {
+        self.registry.disconnect(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::ScreenRegistry;
+
+    #[test]
+    fn connect_and_disconnect() {
+        let registry = ScreenRegistry::new();
+
+        let first = registry.connect(1, Some("10.0.0.1".to_string()), None);
+        let second = registry.connect(1, Some("10.0.0.2".to_string()), None);
+        let other = registry.connect(2, None, Some("Firefox".to_string()));
+        assert_eq!(registry.connections().len(), 3);
+
+        first.event_sent();
+        let connections = registry.connections();
+        assert!(connections[0].last_event.is_some());
+        assert_eq!(connections[1].last_event, None);
+
+        // the screen is still connected through its other client
+        drop(first);
+        assert_eq!(registry.disconnected_since(1), None);
+
+        drop(second);
+        drop(other);
+        assert!(registry.connections().is_empty());
+        assert!(registry.disconnected_since(1).is_some());
+        assert!(registry.disconnected_since(2).is_some());
+        assert_eq!(registry.disconnected_since(3), None);
+    }
+
+    #[test]
+    fn long_disconnections_are_taken_once() {
+        let registry = ScreenRegistry::new();
+        drop(registry.connect(1, None, None));
+
+        assert!(registry
+            .take_long_disconnections(TimeDelta::minutes(5))
+            .is_empty());
+
+        let disconnections = registry.take_long_disconnections(TimeDelta::zero());
+        assert_eq!(disconnections.len(), 1);
+        assert_eq!(disconnections[0].0, 1);
+        assert!(registry
+            .take_long_disconnections(TimeDelta::zero())
+            .is_empty());
+
+        // reconnecting clears the disconnection
+        let _connection = registry.connect(1, None, None);
+        assert_eq!(registry.disconnected_since(1), None);
+    }
+}
@@ -0,0 +1,131 @@
+//! Splits an image spanning the whole wall of screens into the part shown on each screen.
+
+use common::dtos::ScreenLayoutDto;
+
+use crate::media::Crop;
+
+/// Get the part of an image of the given size which is shown on each of the screens, which must
+/// be given in the order they are placed on the wall (i.e. by position).
+///
+/// The image is scaled uniformly to cover the whole wall, including the gaps between the screens,
+/// and centered on it, so parts of it are cut off if its aspect ratio doesn't match the wall's.
+/// Screens which are shorter than the wall are centered vertically.
+pub fn span_crops(image_width: i32, image_height: i32, screens: &[ScreenLayoutDto]) -> Vec<Crop> {
+    // there is no gap after the last screen
+    let gaps: i32 = screens
+        .iter()
+        .rev()
+        .skip(1)
+        .map(|screen| screen.bezel_gap)
+        .sum();
+    let wall_width = screens.iter().map(|screen| screen.width).sum::<i32>() + gaps;
+    let wall_height = screens
+        .iter()
+        .map(|screen| screen.height)
+        .max()
+        .unwrap_or(0);
+    if wall_width <= 0 || wall_height <= 0 || image_width <= 0 || image_height <= 0 {
+        return Vec::new();
+    }
+
+    // wall pixels per image pixel
+    let scale = f64::max(
+        wall_width as f64 / image_width as f64,
+        wall_height as f64 / image_height as f64,
+    );
+    let offset_x = (image_width as f64 - wall_width as f64 / scale) / 2.0;
+    let offset_y = (image_height as f64 - wall_height as f64 / scale) / 2.0;
+
+    let mut wall_x = 0;
+    screens
+        .iter()
+        .map(|screen| {
+            let wall_y = (wall_height - screen.height) as f64 / 2.0;
+            let crop = Crop {
+                x: (offset_x + wall_x as f64 / scale).round() as i32,
+                y: (offset_y + wall_y / scale).round() as i32,
+                width: ((screen.width as f64 / scale).round() as i32).max(1),
+                height: ((screen.height as f64 / scale).round() as i32).max(1),
+                scaled_width: screen.width,
+                scaled_height: screen.height,
+            };
+            wall_x += screen.width + screen.bezel_gap;
+            crop
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use common::dtos::ScreenLayoutDto;
+
+    use super::span_crops;
+    use crate::media::Crop;
+
+    fn full_hd(bezel_gap: i32) -> ScreenLayoutDto {
+        ScreenLayoutDto {
+            bezel_gap,
+            ..Default::default()
+        }
+    }
+
+    fn crop(x: i32, y: i32, width: i32, height: i32) -> Crop {
+        Crop {
+            x,
+            y,
+            width,
+            height,
+            scaled_width: 1920,
+            scaled_height: 1080,
+        }
+    }
+
+    #[test]
+    fn matching_image_is_split_evenly() {
+        let crops = span_crops(5760, 1080, &[full_hd(0), full_hd(0), full_hd(0)]);
+
+        assert_eq!(
+            crops,
+            vec![
+                crop(0, 0, 1920, 1080),
+                crop(1920, 0, 1920, 1080),
+                crop(3840, 0, 1920, 1080),
+            ]
+        );
+    }
+
+    #[test]
+    fn bezel_gaps_are_skipped() {
+        // the gap after the last screen isn't part of the wall
+        let crops = span_crops(5960, 1080, &[full_hd(100), full_hd(100), full_hd(100)]);
+
+        assert_eq!(
+            crops,
+            vec![
+                crop(0, 0, 1920, 1080),
+                crop(2020, 0, 1920, 1080),
+                crop(4040, 0, 1920, 1080),
+            ]
+        );
+    }
+
+    #[test]
+    fn image_is_scaled_and_centered() {
+        // half the resolution of the wall, with extra height which is cut off
+        let crops = span_crops(2880, 740, &[full_hd(0), full_hd(0), full_hd(0)]);
+
+        assert_eq!(
+            crops,
+            vec![
+                crop(0, 100, 960, 540),
+                crop(960, 100, 960, 540),
+                crop(1920, 100, 960, 540),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_screens() {
+        assert!(span_crops(1920, 1080, &[]).is_empty());
+    }
+}
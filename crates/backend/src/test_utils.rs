@@ -87,9 +87,13 @@ pub fn util_create_slide_group(client: &TestClient) {
             title: "Lorem Ipsum".to_string(),
             owner: None,
             priority: 0,
+            takeover: false,
             hidden: false,
             start_date: DateTimeUtc::from_timestamp_nanos(1739471974000000),
             end_date: None,
+            recurrence: None,
+            transition: None,
+            screens: vec![],
         })
         .dispatch();
     assert_created!(response, "/api/slide-group", 1);
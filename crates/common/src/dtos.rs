@@ -2,7 +2,7 @@
 
 use std::{collections::HashMap, fmt::Display};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveTime, Utc, Weekday};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -23,6 +23,9 @@ pub struct ScreenDto {
     pub id: i32,
     pub name: String,
     pub position: i32,
+    // Used for slide groups without a transition of their own.
+    pub transition: TransitionDto,
+    pub layout: ScreenLayoutDto,
 }
 
 #[cfg(feature = "entity")]
@@ -32,10 +35,64 @@ impl From<entity::screen::Model> for ScreenDto {
             id: screen.id,
             name: screen.name,
             position: screen.position,
+            transition: TransitionDto {
+                transition_type: screen.transition.into(),
+                duration: screen.transition_duration_ms,
+            },
+            layout: ScreenLayoutDto {
+                width: screen.width,
+                height: screen.height,
+                bezel_gap: screen.bezel_gap,
+            },
         }
     }
 }
 
+/// Physical layout of a screen on the wall, used to split content spanning all screens.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScreenLayoutDto {
+    // Resolution in pixels.
+    pub width: i32,
+    pub height: i32,
+    // Gap between the screen and the next one, in pixels of this screen.
+    pub bezel_gap: i32,
+}
+
+impl Default for ScreenLayoutDto {
+    fn default() -> Self {
+        Self {
+            width: 1920,
+            height: 1080,
+            bezel_gap: 0,
+        }
+    }
+}
+
+/// Content shown on a screen when no slide group is to be shown on it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FallbackContentDto {
+    // Id of content uploaded for the screen, or None to show nothing.
+    pub content: Option<i32>,
+}
+
+/// Whether a screen is online, based on the clients connected to its feed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ScreenStatusDto {
+    pub screen: i32,
+    pub connections: Vec<ScreenConnectionDto>,
+    // When the last client disconnected, if none are connected. Is None if no client has connected
+    // since the server started.
+    pub disconnected_since: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ScreenConnectionDto {
+    pub remote_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub connected_since: DateTime<Utc>,
+    pub last_event: Option<DateTime<Utc>>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct CreatedDto {
     pub id: i32,
@@ -47,20 +104,48 @@ pub struct CreateScreenDto {
     pub position: i32,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EditScreenDto {
+    pub name: String,
+    pub position: i32,
+    pub transition: TransitionDto,
+    pub layout: ScreenLayoutDto,
+}
+
+/// Priorities of slide groups above this value don't make them show up any more often, so that a
+/// single group can't make the rotation arbitrarily long.
+pub const MAX_PRIORITY: i32 = 9;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
 pub struct SlideGroupDto {
     pub id: i32,
     pub title: String,
     pub priority: i32,
+    pub takeover: bool,
     pub hidden: bool,
     pub created_by: OwnerDto,
     pub start_date: DateTime<Utc>,
     pub end_date: Option<DateTime<Utc>>,
+    pub recurrence: Option<RecurrenceDto>,
+    // None signifies the default transition of each screen.
+    pub transition: Option<TransitionDto>,
+    // Ids of the screens the slide group is shown on. Empty signifies all screens.
+    pub screens: Vec<i32>,
     pub archive_date: Option<DateTime<Utc>>,
     pub published: bool,
     pub slides: Vec<SlideDto>,
 }
 
+/// Weekly time window which limits when a slide group is shown, within its start and end date.
+/// Times are in Swedish time, and the window continues past midnight if `end_time` is not after
+/// `start_time`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecurrenceDto {
+    pub weekdays: Vec<Weekday>,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(untagged)]
 pub enum OwnerDto {
@@ -140,9 +225,13 @@ pub struct CreateSlideGroupDto {
     // The owner of the new slide group. None signifies the currently logged in user.
     pub owner: Option<GroupDto>,
     pub priority: i32,
+    pub takeover: bool,
     pub hidden: bool,
     pub start_date: DateTime<Utc>,
     pub end_date: Option<DateTime<Utc>>,
+    pub recurrence: Option<RecurrenceDto>,
+    pub transition: Option<TransitionDto>,
+    pub screens: Vec<i32>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -150,10 +239,14 @@ pub struct EditSlideGroupDto {
     pub id: i32,
     pub title: String,
     pub priority: i32,
+    pub takeover: bool,
     pub hidden: bool,
     pub created_by: OwnerDto,
     pub start_date: DateTime<Utc>,
     pub end_date: Option<DateTime<Utc>>,
+    pub recurrence: Option<RecurrenceDto>,
+    pub transition: Option<TransitionDto>,
+    pub screens: Vec<i32>,
     pub archive_date: Option<DateTime<Utc>>,
     pub published: bool,
     pub slides: Vec<EditSlideDto>,
@@ -165,10 +258,14 @@ impl From<SlideGroupDto> for EditSlideGroupDto {
             id: value.id,
             title: value.title,
             priority: value.priority,
+            takeover: value.takeover,
             hidden: value.hidden,
             created_by: value.created_by,
             start_date: value.start_date,
             end_date: value.end_date,
+            recurrence: value.recurrence,
+            transition: value.transition,
+            screens: value.screens,
             archive_date: value.archive_date,
             published: value.published,
             slides: value
@@ -178,6 +275,7 @@ impl From<SlideGroupDto> for EditSlideGroupDto {
                     id: slide.id,
                     position: slide.position,
                     archive_date: slide.archive_date,
+                    duration: slide.duration,
                     content: slide
                         .content
                         .into_iter()
@@ -195,11 +293,13 @@ pub enum EditSlideDto {
         id: i32,
         position: i32,
         archive_date: Option<DateTime<Utc>>,
+        duration: Option<i32>, // milliseconds
         // List of content entity IDs.
         content: Vec<i32>,
     },
     New {
         position: i32,
+        duration: Option<i32>, // milliseconds
         // List of content entity IDs.
         content: Vec<i32>,
     },
@@ -221,6 +321,8 @@ pub struct SlideDto {
     pub id: i32,
     pub position: i32,
     pub archive_date: Option<DateTime<Utc>>,
+    // How long the slide is shown on the screens. None signifies the default duration.
+    pub duration: Option<i32>, // milliseconds
     pub content: Vec<ContentDto>,
 }
 
@@ -230,13 +332,103 @@ pub struct CreateContentDto {
     pub content_type: ContentType,
 }
 
+/// Content showing text written in the browser instead of an uploaded file. An optional background
+/// image is uploaded along with it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CreateTextContentDto {
+    pub screen: i32,
+    pub text: TextContentDto,
+}
+
+/// Text shown full-screen on a slide.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TextContentDto {
+    pub markdown: String,
+    /// Colors as `#rrggbb`.
+    pub background_color: String,
+    pub text_color: String,
+}
+
+impl Default for TextContentDto {
+    fn default() -> Self {
+        Self {
+            markdown: String::new(),
+            background_color: "#000000".to_string(),
+            text_color: "#ffffff".to_string(),
+        }
+    }
+}
+
+impl TextContentDto {
+    /// Is `None` if the content isn't text, i.e. it has no markdown.
+    pub fn from_columns(
+        markdown: Option<String>,
+        background_color: Option<String>,
+        text_color: Option<String>,
+    ) -> Option<Self> {
+        let default = Self::default();
+        Some(Self {
+            markdown: markdown?,
+            background_color: background_color.unwrap_or(default.background_color),
+            text_color: text_color.unwrap_or(default.text_color),
+        })
+    }
+}
+
+/// Schemes of the web pages shown on the screens and of the links and images in text slides.
+pub const ALLOWED_URL_SCHEMES: [&str; 2] = ["http", "https"];
+
+/// Content showing an external web page instead of an uploaded file.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CreateUrlContentDto {
+    pub screen: i32,
+    pub url: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
 pub struct ContentDto {
     pub id: i32,
     pub screen: i32,
     pub content_type: ContentType,
+    /// For text, the url of the background image, or empty if there is none.
     pub url: String,
     pub archive_date: Option<DateTime<Utc>>,
+    /// Is only set for text.
+    #[serde(default)]
+    pub text: Option<TextContentDto>,
+    /// Small version of an image, or poster frame of a video, for lists of content. Is `None` if
+    /// there is none, in which case `url` must be used instead.
+    #[serde(default)]
+    pub thumbnail_url: Option<String>,
+    /// Is only set for videos whose metadata could be read.
+    #[serde(default)]
+    pub video: Option<VideoMetadataDto>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VideoMetadataDto {
+    // Is `None` for videos uploaded before codecs were recorded.
+    pub codec: Option<String>,
+    pub width: i32,
+    pub height: i32,
+    pub duration_ms: i32,
+}
+
+impl VideoMetadataDto {
+    /// Is `None` if the size or duration is unknown.
+    pub fn from_columns(
+        codec: Option<String>,
+        width: Option<i32>,
+        height: Option<i32>,
+        duration_ms: Option<i32>,
+    ) -> Option<Self> {
+        Some(Self {
+            codec,
+            width: width?,
+            height: height?,
+            duration_ms: duration_ms?,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
@@ -245,6 +437,10 @@ pub enum ContentType {
     #[default]
     Image,
     Video,
+    /// External web page, whose url is stored instead of an uploaded file.
+    Url,
+    /// Markdown written in the browser, see [`TextContentDto`].
+    Text,
 }
 
 #[cfg(feature = "entity")]
@@ -254,6 +450,8 @@ impl From<ContentType> for entity::sea_orm_active_enums::ContentType {
             ContentType::Html => Self::Html,
             ContentType::Image => Self::Image,
             ContentType::Video => Self::Video,
+            ContentType::Url => Self::Url,
+            ContentType::Text => Self::Text,
         }
     }
 }
@@ -265,15 +463,167 @@ impl From<entity::sea_orm_active_enums::ContentType> for ContentType {
             entity::sea_orm_active_enums::ContentType::Html => Self::Html,
             entity::sea_orm_active_enums::ContentType::Image => Self::Image,
             entity::sea_orm_active_enums::ContentType::Video => Self::Video,
+            entity::sea_orm_active_enums::ContentType::Url => Self::Url,
+            entity::sea_orm_active_enums::ContentType::Text => Self::Text,
         }
     }
 }
 
+/// How a slide replaces the previous one on a screen.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct TransitionDto {
+    pub transition_type: TransitionType,
+    pub duration: i32, // milliseconds
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum TransitionType {
+    // Replace the previous slide immediately.
+    #[default]
+    Cut,
+    Crossfade,
+    SlideLeft,
+    SlideUp,
+}
+
+#[cfg(feature = "entity")]
+impl From<TransitionType> for entity::sea_orm_active_enums::TransitionType {
+    fn from(value: TransitionType) -> Self {
+        match value {
+            TransitionType::Cut => Self::Cut,
+            TransitionType::Crossfade => Self::Crossfade,
+            TransitionType::SlideLeft => Self::SlideLeft,
+            TransitionType::SlideUp => Self::SlideUp,
+        }
+    }
+}
+
+#[cfg(feature = "entity")]
+impl From<entity::sea_orm_active_enums::TransitionType> for TransitionType {
+    fn from(value: entity::sea_orm_active_enums::TransitionType) -> Self {
+        match value {
+            entity::sea_orm_active_enums::TransitionType::Cut => Self::Cut,
+            entity::sea_orm_active_enums::TransitionType::Crossfade => Self::Crossfade,
+            entity::sea_orm_active_enums::TransitionType::SlideLeft => Self::SlideLeft,
+            entity::sea_orm_active_enums::TransitionType::SlideUp => Self::SlideUp,
+        }
+    }
+}
+
+/// The slides to rotate on a screen.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeedDto {
+    // Hash of the entries, which only changes if the feed does.
+    pub version: String,
+    pub entries: Vec<FeedEntryDto>,
+    // When the feed was sent to the screen, which gives it a rough estimate of the server's clock
+    // before it has measured it using `ServerTimeDto`.
+    pub server_time: DateTime<Utc>,
+}
+
+/// The feed a screen would show at a given time.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeedPreviewDto {
+    pub screen: ScreenDto,
+    pub feed: FeedDto,
+}
+
+/// Version of the format of the events on the feed stream, which is increased whenever it changes
+/// in a way that existing clients can't handle.
+pub const SCREEN_FEED_EVENT_VERSION: u32 = 1;
+
+/// Data of every event on the feed stream of a screen. Each event is named after the type of its
+/// payload, see [`ScreenFeedPayloadDto::event_name`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ScreenFeedEventDto {
+    pub version: u32,
+    pub payload: ScreenFeedPayloadDto,
+}
+
+impl ScreenFeedEventDto {
+    pub fn new(payload: ScreenFeedPayloadDto) -> Self {
+        Self {
+            version: SCREEN_FEED_EVENT_VERSION,
+            payload,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", content = "data", rename_all = "kebab-case")]
+pub enum ScreenFeedPayloadDto {
+    /// The feed to show, which is sent again whenever it changes.
+    Feed(FeedDto),
+    /// The feed couldn't be computed, in which case clients keep showing the last one.
+    FeedError(AppErrorDto),
+    /// The current emergency message, which is `None` once it's cleared.
+    Alert(Option<EmergencyMessageDto>),
+    Command(ScreenCommandDto),
+}
+
+impl ScreenFeedPayloadDto {
+    /// Name of the event the payload is sent as. Errors aren't named `error`, since that is
+    /// reserved for connection errors by `EventSource`.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            Self::Feed(_) => "feed",
+            Self::FeedError(_) => "feed-error",
+            Self::Alert(_) => "alert",
+            Self::Command(_) => "command",
+        }
+    }
+}
+
+/// Remote maintenance commands which admins can send to the clients of a screen, which are sent on
+/// their feed stream.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ScreenCommandDto {
+    /// Reload the page.
+    Reload,
+    /// Briefly show the name and id of the screen on top of the slideshow.
+    Identify,
+    /// Remove the cached feed and media, then reload the page.
+    ClearCache,
+    /// Show the entry of the feed with the given (zero-based) index for its duration, after which
+    /// the regular rotation resumes.
+    JumpToSlide { index: usize },
+}
+
+/// Full-screen message which is shown on every screen instead of their feed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EmergencyMessageDto {
+    pub id: i32,
+    pub message: String,
+    pub image_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    // The message is cleared automatically at this time, if set.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CreateEmergencyMessageDto {
+    pub message: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// The server's clock, which screens align their slideshows to instead of their own clocks.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ServerTimeDto {
+    pub time: DateTime<Utc>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct FeedEntryDto {
     pub content_type: ContentType,
     pub url: String,
     pub duration: i32, // milliseconds
+    // End date of the slide group, so that screens can stop showing it while offline.
+    pub end_date: Option<DateTime<Utc>>,
+    // Transition from the previous entry into this one.
+    pub transition: TransitionDto,
+    // Is only set for text, in which case the url is that of the background image, if any.
+    #[serde(default)]
+    pub text: Option<TextContentDto>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -12,9 +12,27 @@ pub struct Model {
     pub slide: Option<i32>,
     pub screen: i32,
     pub content_type: ContentType,
-    /// Object key in the S3 bucket.
+    /// Object key in the S3 bucket. For web pages, the url of the page instead, and for text, the
+    /// key of the background image or empty if there is none.
     pub file_path: String,
     pub archive_date: Option<DateTime>,
+    /// Length of the media in milliseconds, if it is a video.
+    pub duration_ms: Option<i32>,
+    /// Resolution of the media in pixels, if known.
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    /// Markdown source, if it is text.
+    pub markdown: Option<String>,
+    /// Colors of text content, as `#rrggbb`.
+    pub background_color: Option<String>,
+    pub text_color: Option<String>,
+    /// Object keys of the image scaled to fit the screen, and of a small thumbnail of it, without
+    /// metadata. Are `None` if the content isn't an image which has been processed.
+    pub rendition_path: Option<String>,
+    /// For videos, the key of a poster frame of the size of a thumbnail instead.
+    pub thumbnail_path: Option<String>,
+    /// Name of the codec of the video as given by `ffprobe`, e.g. `h264`.
+    pub video_codec: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
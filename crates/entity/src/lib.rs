@@ -3,9 +3,11 @@
 pub mod prelude;
 
 pub mod content;
+pub mod emergency_message;
 pub mod screen;
 pub mod sea_orm_active_enums;
 pub mod slide;
 pub mod slide_group;
+pub mod slide_group_screen;
 
 pub use sea_orm;
@@ -1,6 +1,8 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.1
 
 pub use super::content::Entity as Content;
+pub use super::emergency_message::Entity as EmergencyMessage;
 pub use super::screen::Entity as Screen;
 pub use super::slide::Entity as Slide;
 pub use super::slide_group::Entity as SlideGroup;
+pub use super::slide_group_screen::Entity as SlideGroupScreen;
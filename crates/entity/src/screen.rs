@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.1
 
+use super::sea_orm_active_enums::TransitionType;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -9,12 +10,32 @@ pub struct Model {
     pub id: i32,
     pub name: String,
     pub position: i32,
+    /// Transition used for slide groups which don't have their own.
+    pub transition: TransitionType,
+    pub transition_duration_ms: i32,
+    /// Content shown when no slide group is to be shown on the screen.
+    pub fallback_content: Option<i32>,
+    /// Resolution of the screen, in pixels.
+    pub width: i32,
+    pub height: i32,
+    /// Gap between the screen and the next one on the wall, in pixels of this screen.
+    pub bezel_gap: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::content::Entity")]
     Content,
+    #[sea_orm(
+        belongs_to = "super::content::Entity",
+        from = "Column::FallbackContent",
+        to = "super::content::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    FallbackContent,
+    #[sea_orm(has_many = "super::slide_group_screen::Entity")]
+    SlideGroupScreen,
 }
 
 impl Related<super::content::Entity> for Entity {
@@ -23,4 +44,10 @@ impl Related<super::content::Entity> for Entity {
     }
 }
 
+impl Related<super::slide_group_screen::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SlideGroupScreen.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -11,4 +11,21 @@ pub enum ContentType {
     Image,
     #[sea_orm(string_value = "video")]
     Video,
+    #[sea_orm(string_value = "url")]
+    Url,
+    #[sea_orm(string_value = "text")]
+    Text,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "transition_type")]
+pub enum TransitionType {
+    #[sea_orm(string_value = "cut")]
+    Cut,
+    #[sea_orm(string_value = "crossfade")]
+    Crossfade,
+    #[sea_orm(string_value = "slide_left")]
+    SlideLeft,
+    #[sea_orm(string_value = "slide_up")]
+    SlideUp,
 }
@@ -10,6 +10,8 @@ pub struct Model {
     pub position: i32,
     pub group: i32,
     pub archive_date: Option<DateTime>,
+    /// How long the slide is shown for, in milliseconds. Uses the default duration if `None`.
+    pub duration_ms: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
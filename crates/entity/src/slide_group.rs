@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.1
 
+use super::sea_orm_active_enums::TransitionType;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -15,12 +16,27 @@ pub struct Model {
     pub end_date: Option<DateTime>,
     pub archive_date: Option<DateTime>,
     pub published: bool,
+    /// If set, only slide groups with takeover enabled are shown while this group is active.
+    pub takeover: bool,
+    /// Bitmask of the weekdays (Monday is the least significant bit) on which the group is shown.
+    /// The group isn't restricted to a weekly time window if `None`.
+    pub recurrence_weekdays: Option<i32>,
+    /// Daily time window in Swedish time, which wraps around midnight if the end is before the
+    /// start. Only set together with `recurrence_weekdays`.
+    pub recurrence_start_time: Option<Time>,
+    pub recurrence_end_time: Option<Time>,
+    /// Transition into the slides of this group. The screen's default is used if `None`.
+    pub transition: Option<TransitionType>,
+    /// Only set together with `transition`.
+    pub transition_duration_ms: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::slide::Entity")]
     Slide,
+    #[sea_orm(has_many = "super::slide_group_screen::Entity")]
+    SlideGroupScreen,
 }
 
 impl Related<super::slide::Entity> for Entity {
@@ -29,4 +45,10 @@ impl Related<super::slide::Entity> for Entity {
     }
 }
 
+impl Related<super::slide_group_screen::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SlideGroupScreen.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,133 @@
+// Service worker for the screen feed pages (`/feed/:id`), which keeps the slideshow running while
+// the backend or S3 is unreachable.
+//
+// - The app itself (HTML, wasm, scripts and styles) is fetched from the network when possible,
+//   falling back to the last cached version.
+// - Media referenced by the feed is cached when the page sends a `cache-media` message, and served
+//   from the cache from then on, as media URLs contain a hash of their content.
+// - The feed itself is stored by the page, since event streams can't be cached.
+
+const APP_CACHE = "meta-tv-app-v1";
+const MEDIA_CACHE = "meta-tv-media-v1";
+
+self.addEventListener("install", () => {
+  self.skipWaiting();
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    (async () => {
+      for (const name of await caches.keys()) {
+        if (name !== APP_CACHE && name !== MEDIA_CACHE) {
+          await caches.delete(name);
+        }
+      }
+      await self.clients.claim();
+    })(),
+  );
+});
+
+// messages are sent from the page as JSON strings
+self.addEventListener("message", (event) => {
+  const message = JSON.parse(event.data);
+  if (message.type === "cache-media") {
+    event.waitUntil(cacheMedia(message.urls));
+  }
+});
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  if (request.method !== "GET") {
+    return;
+  }
+
+  const url = new URL(request.url);
+  if (url.origin === self.location.origin) {
+    // the api and authentication must never be served stale
+    if (url.pathname.startsWith("/api/") || url.pathname.startsWith("/auth/")) {
+      return;
+    }
+    event.respondWith(networkFirst(request));
+  } else {
+    event.respondWith(cachedMedia(request));
+  }
+});
+
+async function networkFirst(request) {
+  const cache = await caches.open(APP_CACHE);
+  try {
+    const response = await fetch(request);
+    if (response.ok) {
+      // all screen pages are the same single page app
+      const key = request.mode === "navigate" ? "/index.html" : request;
+      await cache.put(key, response.clone());
+    }
+    return response;
+  } catch (err) {
+    const cached = await cache.match(request.mode === "navigate" ? "/index.html" : request);
+    if (cached) {
+      return cached;
+    }
+    throw err;
+  }
+}
+
+async function cachedMedia(request) {
+  const cache = await caches.open(MEDIA_CACHE);
+  const cached = await cache.match(request.url);
+  if (!cached) {
+    return fetch(request);
+  }
+
+  // videos are requested in ranges, which must be answered with only the requested bytes
+  const range = request.headers.get("range");
+  if (range && cached.type !== "opaque") {
+    return rangeResponse(cached, range);
+  }
+  return cached;
+}
+
+async function rangeResponse(response, range) {
+  const blob = await response.blob();
+  const [, startText, endText] = /bytes=(\d*)-(\d*)/.exec(range) ?? [];
+  const start = startText ? Number(startText) : 0;
+  const end = endText ? Number(endText) + 1 : blob.size;
+
+  return new Response(blob.slice(start, end), {
+    status: 206,
+    headers: {
+      "Content-Type": response.headers.get("Content-Type") ?? "",
+      "Content-Range": `bytes ${start}-${end - 1}/${blob.size}`,
+      "Content-Length": String(end - start),
+    },
+  });
+}
+
+// Cache the given media, and remove media which is no longer part of the feed.
+async function cacheMedia(urls) {
+  const cache = await caches.open(MEDIA_CACHE);
+  const wanted = new Set(urls);
+
+  for (const request of await cache.keys()) {
+    if (!wanted.has(request.url)) {
+      await cache.delete(request);
+    }
+  }
+
+  for (const url of wanted) {
+    if (await cache.match(url)) {
+      continue;
+    }
+    try {
+      // prefer a readable response, which is needed to answer range requests for videos
+      const response = await fetch(url, { mode: "cors" }).catch(() =>
+        fetch(url, { mode: "no-cors" }),
+      );
+      if (response.ok || response.type === "opaque") {
+        await cache.put(url, response);
+      }
+    } catch (err) {
+      console.warn(`Failed to cache ${url}`, err);
+    }
+  }
+}
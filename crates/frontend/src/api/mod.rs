@@ -1,6 +1,9 @@
+use chrono::{DateTime, SecondsFormat, Utc};
 use common::dtos::{
-    AppErrorDto, ContentDto, CreateContentDto, CreateSlideGroupDto, CreatedDto, EditSlideGroupDto,
-    ScreenDto, SlideGroupDto, UserInfoDto,
+    AppErrorDto, ContentDto, CreateContentDto, CreateSlideGroupDto, CreateTextContentDto,
+    CreateUrlContentDto, CreatedDto, EditScreenDto, EditSlideGroupDto, FallbackContentDto,
+    FeedPreviewDto, ScreenCommandDto, ScreenDto, ScreenStatusDto, ServerTimeDto, SlideGroupDto,
+    UserInfoDto,
 };
 use gloo_net::http::{Request, Response};
 use leptos::{logging, server_fn::serde::de::DeserializeOwned};
@@ -67,6 +70,52 @@ pub async fn list_screens() -> Result<Vec<ScreenDto>, AppError> {
     handle_response(Request::get("/api/screen").send().await?).await
 }
 
+pub async fn list_screen_statuses() -> Result<Vec<ScreenStatusDto>, AppError> {
+    handle_response(Request::get("/api/screen/status").send().await?).await
+}
+
+pub async fn update_screen(id: i32, screen: &EditScreenDto) -> Result<(), AppError> {
+    handle_blank_response(
+        Request::put(&format!("/api/screen/{id}"))
+            .json(screen)?
+            .send()
+            .await?,
+    )
+    .await
+}
+
+/// Send a command to the clients of the given screen, or of every screen if `None`.
+pub async fn send_screen_command(
+    screen: Option<i32>,
+    command: &ScreenCommandDto,
+) -> Result<(), AppError> {
+    let url = match screen {
+        Some(screen) => format!("/api/screen/{screen}/command"),
+        None => "/api/screen/command".to_string(),
+    };
+    handle_blank_response(Request::post(&url).json(command)?.send().await?).await
+}
+
+pub async fn get_fallback_content(screen: i32) -> Result<Option<ContentDto>, AppError> {
+    handle_response(
+        Request::get(&format!("/api/screen/{screen}/fallback-content"))
+            .send()
+            .await?,
+    )
+    .await
+}
+
+/// Set the content shown on the screen when no slide group is to be shown, or remove it if `None`.
+pub async fn set_fallback_content(screen: i32, content: Option<i32>) -> Result<(), AppError> {
+    handle_blank_response(
+        Request::put(&format!("/api/screen/{screen}/fallback-content"))
+            .json(&FallbackContentDto { content })?
+            .send()
+            .await?,
+    )
+    .await
+}
+
 pub async fn list_slide_groups() -> Result<Vec<SlideGroupDto>, AppError> {
     handle_response(Request::get("/api/slide-group").send().await?).await
 }
@@ -113,6 +162,58 @@ pub async fn upload_content(data: &CreateContentDto, file: &File) -> Result<Cont
     .await
 }
 
+/// Add a web page by url instead of uploading a file.
+pub async fn create_url_content(data: &CreateUrlContentDto) -> Result<ContentDto, AppError> {
+    handle_response(Request::post("/api/content/url").json(data)?.send().await?).await
+}
+
+/// Add text written in the browser, with an optional background image.
+pub async fn create_text_content(
+    data: &CreateTextContentDto,
+    background: Option<&File>,
+) -> Result<ContentDto, AppError> {
+    let form_data = FormData::new()?;
+    form_data.set_with_str("data", &serde_json::to_string(data)?)?;
+    if let Some(background) = background {
+        form_data.set_with_blob("background", background)?;
+    }
+    handle_response(
+        Request::post("/api/content/text")
+            .body(form_data)?
+            .send()
+            .await?,
+    )
+    .await
+}
+
+/// Render every page of a PDF as an image on the given screen, returning the content created for
+/// each page in order.
+pub async fn upload_pdf_content(screen: i32, file: &File) -> Result<Vec<ContentDto>, AppError> {
+    let form_data = FormData::new()?;
+    form_data.set_with_str("screen", &screen.to_string())?;
+    form_data.set_with_blob("file", file)?;
+    handle_response(
+        Request::post("/api/content/pdf")
+            .body(form_data)?
+            .send()
+            .await?,
+    )
+    .await
+}
+
+/// Split an image across all screens, returning the content created for each screen.
+pub async fn upload_span_content(file: &File) -> Result<Vec<ContentDto>, AppError> {
+    let form_data = FormData::new()?;
+    form_data.set_with_blob("file", file)?;
+    handle_response(
+        Request::post("/api/content/span")
+            .body(form_data)?
+            .send()
+            .await?,
+    )
+    .await
+}
+
 pub async fn archive_slide_group(id: i32) -> Result<(), AppError> {
     handle_blank_response(
         Request::delete(&format!("/api/slide-group/{id}"))
@@ -122,6 +223,28 @@ pub async fn archive_slide_group(id: i32) -> Result<(), AppError> {
     .await
 }
 
+pub async fn get_server_time() -> Result<ServerTimeDto, AppError> {
+    handle_response(Request::get("/api/time").send().await?).await
+}
+
+/// Get the feed of every screen as it would be at the given time, optionally including the
+/// unpublished slide groups owned by the user.
+pub async fn preview_feeds(
+    at: DateTime<Utc>,
+    unpublished: bool,
+) -> Result<Vec<FeedPreviewDto>, AppError> {
+    handle_response(
+        Request::get("/api/feed/preview")
+            .query([
+                ("at", at.to_rfc3339_opts(SecondsFormat::Secs, true)),
+                ("unpublished", unpublished.to_string()),
+            ])
+            .send()
+            .await?,
+    )
+    .await
+}
+
 pub fn get_screen_feed_url(screen_id: i32) -> String {
     format!("/api/feed/{screen_id}")
 }
@@ -1,4 +1,7 @@
-use common::dtos::{ContentDto, ContentType, CreateContentDto, ScreenDto};
+use common::dtos::{
+    ContentDto, ContentType, CreateContentDto, CreateTextContentDto, CreateUrlContentDto,
+    ScreenDto, TextContentDto,
+};
 use icondata as i;
 use leptos::prelude::*;
 use leptos_icons::Icon;
@@ -6,8 +9,8 @@ use web_sys::File;
 
 use crate::{
     api,
-    components::{dialog::Dialog, error::ErrorList},
-    utils::dom_id::next_dom_id,
+    components::{dialog::Dialog, error::ErrorList, text_slide::TextSlide},
+    utils::{datetime::fmt_duration_ms, dom_id::next_dom_id},
 };
 
 #[component]
@@ -17,32 +20,73 @@ pub fn ContentItem(
     // Function which is called with the resulting content ID after the user has uploaded content.
     on_submit: impl Fn(ContentDto) -> () + 'static,
     #[prop(into)] editable: Signal<bool>,
+    /// Allow splitting an uploaded image across all screens, in which case `on_submit` is called
+    /// with the content of every screen.
+    #[prop(optional)]
+    allow_span: bool,
 ) -> impl IntoView {
     let is_upload_dialog_open = RwSignal::new(false);
     let content_description_id = next_dom_id("content-description");
 
-    let display_content = |content: ContentDto| match content.content_type {
-        ContentType::Image => {
-            view! { <img class="object-contain h-full w-full" src=&content.url /> }.into_any()
-        }
-        ContentType::Video => view! {
-            <video
-                controls
-                muted
-                preload="metadata"
-                class="object-contain h-full w-full"
-                src=&content.url
-            />
-        }
-        .into_any(),
-        ContentType::Html => view! {
-            <iframe
-                sandbox="allow-scripts allow-same-origin"
-                class="object-contain h-full w-full pointer-events-none"
-                src=&content.url
-            />
+    let display_content = |content: ContentDto| {
+        match content.content_type {
+            ContentType::Image => {
+                let src = content.thumbnail_url.unwrap_or(content.url);
+                view! { <img class="object-contain h-full w-full" src=src /> }.into_any()
+            }
+            // the poster is shown instead of the video if there is one, so that the video isn't
+            // loaded
+            ContentType::Video => match content.thumbnail_url {
+                Some(poster) => view! {
+                    <div class="relative h-full w-full">
+                        <img class="object-contain h-full w-full" src=poster />
+                        <span class="absolute inset-0 flex justify-center items-center text-white drop-shadow">
+                            <Icon icon=i::MdiPlayCircleOutline width="3em" height="3em" />
+                        </span>
+                        {content
+                            .video
+                            .map(|video| {
+                                view! {
+                                    <p class="absolute bottom-0 right-0 m-1 px-1 rounded bg-black/60 text-white text-xs">
+                                        {video
+                                            .codec
+                                            .into_iter()
+                                            .chain([
+                                                format!("{}\u{d7}{}", video.width, video.height),
+                                                fmt_duration_ms(video.duration_ms),
+                                            ])
+                                            .collect::<Vec<_>>()
+                                            .join(" \u{b7} ")}
+                                    </p>
+                                }
+                            })}
+                    </div>
+                }
+                .into_any(),
+                None => view! {
+                    <video
+                        controls
+                        muted
+                        preload="metadata"
+                        class="object-contain h-full w-full"
+                        src=&content.url
+                    />
+                }
+                .into_any(),
+            },
+            ContentType::Text => view! {
+                <TextSlide text=content.text.unwrap_or_default() background=content.url />
+            }
+            .into_any(),
+            ContentType::Html | ContentType::Url => view! {
+                <iframe
+                    sandbox="allow-scripts allow-same-origin"
+                    class="object-contain h-full w-full pointer-events-none"
+                    src=&content.url
+                />
+            }
+            .into_any(),
         }
-        .into_any(),
     };
 
     view! {
@@ -58,6 +102,8 @@ pub fn ContentItem(
                     screen_id=screen.id
                     open=is_upload_dialog_open
                     on_submit=on_submit
+                    allow_span
+                    current=content
                 />
                 {move || {
                     match (content.get(), editable.get()) {
@@ -65,7 +111,7 @@ pub fn ContentItem(
                             view! {
                                 <button
                                     class="block h-full w-full"
-                                    aria-label="Upload image"
+                                    aria-label="Upload content"
                                     aria-describedby=content_description_id.clone()
                                     on:click=move |_| is_upload_dialog_open.set(true)
                                 >
@@ -103,6 +149,25 @@ pub fn ContentItem(
     }.into_any()
 }
 
+/// Where new content comes from.
+#[derive(Clone)]
+enum ContentSource {
+    /// Uploaded file, and whether to split it across all screens.
+    File(File, bool),
+    /// External web page.
+    Url(String),
+    /// Markdown, with an optional background image.
+    Text(TextContentDto, Option<File>),
+}
+
+/// Tabs of [`UploadContentDialog`], one for each kind of [`ContentSource`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SourceTab {
+    File,
+    Url,
+    Text,
+}
+
 #[component]
 pub fn UploadContentDialog(
     #[prop()] screen_id: i32,
@@ -110,24 +175,73 @@ pub fn UploadContentDialog(
     // Function which is called with the resulting populated content after the user has uploaded
     // content.
     on_submit: impl Fn(ContentDto) + 'static,
+    /// Allow splitting an uploaded image across all screens, in which case `on_submit` is called
+    /// with the content of every screen.
+    #[prop(optional)]
+    allow_span: bool,
+    /// Content which is replaced, so that text can be edited instead of written from scratch.
+    #[prop(optional, into)]
+    current: MaybeProp<ContentDto>,
 ) -> impl IntoView {
     let input_ref = NodeRef::new();
+    let url_input_ref = NodeRef::new();
+    let background_input_ref = NodeRef::new();
+    let span = RwSignal::new(false);
+    let tab = RwSignal::new(SourceTab::File);
+    let text = RwSignal::new(TextContentDto::default());
 
-    let upload_action = Action::new_local(move |file: &File| {
-        let file = file.clone();
-        let mime_type = file.type_();
-        let content_type = if mime_type.starts_with("image/") {
-            ContentType::Image
-        } else if mime_type.starts_with("video/") {
-            ContentType::Video
-        } else {
-            ContentType::Html
-        };
-        let data = CreateContentDto {
-            screen: screen_id,
-            content_type,
-        };
-        async move { api::upload_content(&data, &file).await }
+    // start from the current text whenever the dialog is opened
+    Effect::new(move || {
+        if !open.get() {
+            return;
+        }
+        if let Some(current) = untrack(|| current.get()).and_then(|content| content.text) {
+            tab.set(SourceTab::Text);
+            text.set(current);
+        }
+    });
+
+    let upload_action = Action::new_local(move |source: &ContentSource| {
+        let source = source.clone();
+        async move {
+            match source {
+                ContentSource::File(file, span) => {
+                    let mime_type = file.type_();
+                    let content_type = if mime_type.starts_with("image/") {
+                        ContentType::Image
+                    } else if mime_type.starts_with("video/") {
+                        ContentType::Video
+                    } else {
+                        ContentType::Html
+                    };
+                    if span && content_type == ContentType::Image {
+                        api::upload_span_content(&file).await
+                    } else {
+                        let data = CreateContentDto {
+                            screen: screen_id,
+                            content_type,
+                        };
+                        Ok(vec![api::upload_content(&data, &file).await?])
+                    }
+                }
+                ContentSource::Url(url) => {
+                    let data = CreateUrlContentDto {
+                        screen: screen_id,
+                        url,
+                    };
+                    Ok(vec![api::create_url_content(&data).await?])
+                }
+                ContentSource::Text(text, background) => {
+                    let data = CreateTextContentDto {
+                        screen: screen_id,
+                        text,
+                    };
+                    Ok(vec![
+                        api::create_text_content(&data, background.as_ref()).await?,
+                    ])
+                }
+            }
+        }
     });
 
     let is_submitting = upload_action.pending();
@@ -138,34 +252,142 @@ pub fn UploadContentDialog(
             // Make sure that the caller doesn't accidentally subscribe this effect to other
             // dependencies.
             untrack(|| {
-                on_submit(created);
+                for content in created {
+                    on_submit(content);
+                }
             });
         }
     });
 
+    let tab_button = move |value: SourceTab, label: &'static str| {
+        view! {
+            <button
+                type="button"
+                role="tab"
+                class="tab"
+                class:tab-active=move || tab.get() == value
+                on:click=move |_| tab.set(value)
+            >
+                {label}
+            </button>
+        }
+    };
+    let is_tab = move |value: SourceTab| move || tab.get() == value;
+    let is_not_tab = move |value: SourceTab| move || tab.get() != value;
+
     view! {
         <Dialog open=open>
             <div class="card space-y-6 p-4">
                 <form on:submit=move |ev| {
                     ev.prevent_default();
-                    if let Some(file) = input_ref
-                        .get()
-                        .and_then(|input| { input.files() })
-                        .and_then(|filelist| filelist.item(0))
-                    {
-                        upload_action.dispatch_local(file);
+                    match tab.get_untracked() {
+                        SourceTab::File => {
+                            if let Some(file) = input_ref
+                                .get()
+                                .and_then(|input| { input.files() })
+                                .and_then(|filelist| filelist.item(0))
+                            {
+                                upload_action
+                                    .dispatch_local(ContentSource::File(file, span.get_untracked()));
+                            }
+                        }
+                        SourceTab::Url => {
+                            if let Some(input) = url_input_ref.get() {
+                                upload_action.dispatch_local(ContentSource::Url(input.value()));
+                            }
+                        }
+                        SourceTab::Text => {
+                            let background = background_input_ref
+                                .get()
+                                .and_then(|input| { input.files() })
+                                .and_then(|filelist| filelist.item(0));
+                            upload_action
+                                .dispatch_local(ContentSource::Text(text.get_untracked(), background));
+                        }
                     }
                 }>
                     <fieldset disabled=is_submitting>
-                        <div class="w-2xs overflow-hidden">
+                        <div role="tablist" class="tabs tabs-box w-fit mb-4">
+                            {tab_button(SourceTab::File, "File")}
+                            {tab_button(SourceTab::Url, "Web page")}
+                            {tab_button(SourceTab::Text, "Text")}
+                        </div>
+                        <div class="w-2xs overflow-hidden" class:hidden=is_not_tab(SourceTab::File)>
                             <input
                                 class="rounded-sm input"
                                 node_ref=input_ref
                                 type="file"
                                 accept="image/*,video/*,text/html"
-                                required="true"
+                                required=is_tab(SourceTab::File)
+                            />
+                        </div>
+                        <div class="w-2xs" class:hidden=is_not_tab(SourceTab::Url)>
+                            <input
+                                class="input"
+                                node_ref=url_input_ref
+                                type="url"
+                                placeholder="https://"
+                                pattern="https?://.*"
+                                required=is_tab(SourceTab::Url)
+                            />
+                        </div>
+                        <div class="w-md space-y-4" class:hidden=is_not_tab(SourceTab::Text)>
+                            <textarea
+                                class="textarea w-full h-32 font-mono"
+                                placeholder="# Headline"
+                                prop:value=move || text.with(|text| text.markdown.clone())
+                                on:input:target=move |ev| {
+                                    text.update(|text| text.markdown = ev.target().value());
+                                }
                             />
+                            <p class="text-sm/6 text-current/60">
+                                "Use # for headlines and *stars* for emphasis. Longer text is made smaller to fit."
+                            </p>
+                            <div class="flex flex-wrap gap-4">
+                                <label class="label">
+                                    <input
+                                        type="color"
+                                        prop:value=move || {
+                                            text.with(|text| text.background_color.clone())
+                                        }
+                                        on:input:target=move |ev| {
+                                            text.update(|text| {
+                                                text.background_color = ev.target().value();
+                                            });
+                                        }
+                                    />
+                                    "Background"
+                                </label>
+                                <label class="label">
+                                    <input
+                                        type="color"
+                                        prop:value=move || text.with(|text| text.text_color.clone())
+                                        on:input:target=move |ev| {
+                                            text.update(|text| text.text_color = ev.target().value());
+                                        }
+                                    />
+                                    "Text"
+                                </label>
+                            </div>
+                            <label class="input w-full">
+                                <span class="label">"Background image"</span>
+                                <input node_ref=background_input_ref type="file" accept="image/*" />
+                            </label>
+                            <div class="aspect-16/9 border">
+                                {move || view! { <TextSlide text=text.get() background="" /> }}
+                            </div>
                         </div>
+                        <Show when=move || allow_span && tab.get() == SourceTab::File>
+                            <label class="label mt-4">
+                                <input
+                                    type="checkbox"
+                                    class="checkbox"
+                                    prop:checked=span
+                                    on:change:target=move |ev| span.set(ev.target().checked())
+                                />
+                                "Split image across all screens"
+                            </label>
+                        </Show>
                         <div class="mt-6 flex gap-3">
                             <button class="btn" type="submit">
                                 "Upload"
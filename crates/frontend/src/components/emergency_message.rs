@@ -0,0 +1,15 @@
+use common::dtos::EmergencyMessageDto;
+use leptos::prelude::*;
+
+/// Full-screen emergency message, which is shown on top of the slideshow until it's cleared.
+#[component]
+pub fn EmergencyMessage(message: EmergencyMessageDto) -> impl IntoView {
+    view! {
+        <div class="fixed inset-0 z-50 flex flex-col items-center justify-center gap-12 bg-red-800 p-16 text-white">
+            {message
+                .image_url
+                .map(|url| view! { <img class="max-h-[50vh] max-w-full object-contain" src=url /> })}
+            <p class="whitespace-pre-line text-center text-6xl font-bold">{message.message}</p>
+        </div>
+    }
+}
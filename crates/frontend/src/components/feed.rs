@@ -1,111 +1,452 @@
-use chrono::Utc;
-use common::dtos::{ContentType, FeedEntryDto};
+use chrono::{DateTime, Utc};
+use common::dtos::{ContentType, FeedEntryDto, TransitionDto, TransitionType};
 use gloo_timers::callback::Timeout;
-use leptos::prelude::*;
+use leptos::{html::Video, prelude::*};
+
+use crate::{components::text_slide::TextSlide, utils::clock::ServerClock};
 
 #[component]
-/// Handle the feed data and change slides based on time
-pub fn ScreenFeedSlideshow(feed: Signal<Vec<FeedEntryDto>>) -> impl IntoView {
+/// Handle the feed data and change slides based on time.
+///
+/// The `lookahead` entries after the current one are mounted while hidden, so that their content
+/// has usually already been downloaded when it's their turn. The previous entry is kept mounted
+/// until the transition into the current one has finished.
+///
+/// Buffering is best-effort: the rotation is never delayed for it, since the screens must stay
+/// aligned with each other. A video which can't be played through yet when it's its turn waits
+/// until it can, and then starts from where the other screens are.
+pub fn ScreenFeedSlideshow(
+    feed: Signal<Vec<FeedEntryDto>>,
+    clock: ServerClock,
+    lookahead: usize,
+    /// Temporarily show a given entry instead of following the rotation.
+    #[prop(into)]
+    jump: Signal<Option<SlideJump>>,
+) -> impl IntoView {
     let refresh_slide_signal = RwSignal::new(0);
     let timeout_handle_signal = RwSignal::new_local(None::<Timeout>);
-    let (slide, set_slide) = signal(None);
+    let transition_handle_signal = RwSignal::new_local(None::<Timeout>);
+    let current = RwSignal::new(None::<CurrentSlide>);
+    let current_index = Memo::new(move |_| current.get().map(|current| current.index));
+    let transition = RwSignal::new(None::<ActiveTransition>);
+    // The entries of the feed whose slide group hasn't ended, which matters if the feed is stale
+    // because the screen is offline
+    let active_feed = RwSignal::new(Vec::<FeedEntryDto>::new());
 
     Effect::new(move || {
         refresh_slide_signal.track();
-        let feed = feed.read();
+        clock.track();
+        let now = clock.now();
+        let feed = active_entries(&feed.read(), now);
+        let feed_changed = active_feed.with_untracked(|active_feed| *active_feed != feed);
+        if feed_changed {
+            active_feed.set(feed.clone());
+        }
+
         timeout_handle_signal.update(|handle| {
             if let Some(handle) = handle.take() {
                 handle.cancel();
             }
         });
-        if let Some((timeout, entry)) = calculate_next_slide(&feed) {
-            set_slide.set(Some(entry.clone()));
-            let handle = Timeout::new(timeout, move || {
-                *refresh_slide_signal.write() += 1;
-            });
-            timeout_handle_signal.set(Some(handle));
+        let jumped_slide = jump.get().and_then(|jump| jump.next_slide(&feed, now));
+        let Some(next_slide) = jumped_slide.or_else(|| calculate_next_slide(&feed, now)) else {
+            current.set(None);
+            transition.set(None);
+            return;
+        };
+        let next_current = CurrentSlide {
+            index: next_slide.index,
+            started_at: now.timestamp_millis() - next_slide.elapsed as i64,
+        };
+
+        // Transitions are based on the time since the slide started rather than when this runs,
+        // so that they are in sync on all screens
+        let previous = current.get_untracked();
+        if feed_changed {
+            transition.set(None);
+        } else if let Some(previous) = previous
+            && previous.index != next_current.index
+        {
+            let entry_transition = feed[next_slide.index].transition;
+            let remaining = entry_transition.duration - next_slide.elapsed as i32;
+            if entry_transition.transition_type != TransitionType::Cut && remaining > 0 {
+                transition.set(Some(ActiveTransition {
+                    outgoing_index: previous.index,
+                    transition: entry_transition,
+                    elapsed: next_slide.elapsed,
+                }));
+                let handle = Timeout::new(remaining as u32, move || transition.set(None));
+                transition_handle_signal.update(|handle_slot| {
+                    if let Some(previous) = handle_slot.replace(handle) {
+                        previous.cancel();
+                    }
+                });
+            } else {
+                transition.set(None);
+            }
         }
+
+        // Only notify when a new slide starts, since e.g. videos are restarted then
+        if previous.is_none_or(|previous| !previous.is_same_turn(&next_current)) {
+            current.set(Some(next_current));
+        }
+        let handle = Timeout::new(next_slide.remaining, move || {
+            *refresh_slide_signal.write() += 1;
+        });
+        timeout_handle_signal.set(Some(handle));
     });
     on_cleanup(move || {
-        timeout_handle_signal.update(|handle| {
-            if let Some(handle) = handle.take() {
-                handle.cancel();
-            }
-        });
+        for handle_signal in [timeout_handle_signal, transition_handle_signal] {
+            handle_signal.update(|handle| {
+                if let Some(handle) = handle.take() {
+                    handle.cancel();
+                }
+            });
+        }
     });
 
+    // The current entry followed by the upcoming ones, and the outgoing one during a transition.
+    // Entries are keyed by their position in the feed so that they stay mounted when going from
+    // upcoming to current to outgoing.
+    let mounted_entries = move || {
+        let feed = active_feed.read();
+        let Some(current) = current_index.get() else {
+            return Vec::new();
+        };
+
+        let mut indices: Vec<usize> = (0..=lookahead.min(feed.len().saturating_sub(1)))
+            .map(|offset| (current + offset) % feed.len())
+            .collect();
+        if let Some(transition) = transition.get()
+            && transition.outgoing_index < feed.len()
+            && !indices.contains(&transition.outgoing_index)
+        {
+            indices.push(transition.outgoing_index);
+        }
+
+        indices
+            .into_iter()
+            .map(|index| (index, feed[index].clone()))
+            .collect::<Vec<_>>()
+    };
+
     view! {
-        {move || {
-            slide
-                .read()
-                .as_ref()
-                .map(|entry| {
-                    match entry.content_type {
-                        ContentType::Image => {
-                            view! {
-                                <img
-                                    class="object-contain h-screen w-screen"
-                                    src=&entry.url
-                                />
-                            }
-                                .into_any()
+        <div class="relative h-screen w-screen overflow-hidden">
+            <For
+                each=mounted_entries
+                key=|(index, entry)| (*index, entry.url.clone())
+                children=move |(index, entry)| {
+                    let is_current = Signal::derive(move || {
+                        current.get().is_some_and(|current| current.index == index)
+                    });
+                    let animation = Signal::derive(move || {
+                        let transition = transition.get()?;
+                        if is_current.get() {
+                            transition.animation(true)
+                        } else if transition.outgoing_index == index {
+                            transition.animation(false)
+                        } else {
+                            None
                         }
-                        ContentType::Video => {
-                            view! {
-                                <video
-                                    autoplay
-                                    loop
-                                    muted
-                                    preload="auto"
-                                    playsinline
-                                    class="object-contain h-screen w-screen"
-                                    src=&entry.url
-                                    on:loadedmetadata:target=move |ev| {
-                                        let target = ev.target();
-                                        target.set_muted(true);
-                                        target.set_loop(true);
-                                        let _ = target.play();
-                                    }
-                                />
-                            }
-                                .into_any()
+                    });
+                    let is_outgoing = Signal::derive(move || {
+                        !is_current.get()
+                            && transition
+                                .get()
+                                .is_some_and(|transition| transition.outgoing_index == index)
+                    });
+                    let started_at = Signal::derive(move || {
+                        current
+                            .get()
+                            .filter(|current| current.index == index)
+                            .map(|current| current.started_at)
+                    });
+                    view! { <FeedEntry entry is_current is_outgoing animation started_at clock /> }
+                }
+            />
+        </div>
+    }
+    .into_any()
+}
+
+/// Slides are considered to be the same turn of an entry if their start times differ by less than
+/// this, which happens when the clock offset is corrected.
+const SAME_TURN_TOLERANCE_MS: i64 = 1_000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct CurrentSlide {
+    index: usize,
+    /// When the slide started being displayed, in milliseconds since the Unix epoch.
+    started_at: i64,
+}
+
+impl CurrentSlide {
+    fn is_same_turn(&self, other: &CurrentSlide) -> bool {
+        self.index == other.index
+            && (self.started_at - other.started_at).abs() < SAME_TURN_TOLERANCE_MS
+    }
+}
+
+/// Transition from the previous entry into the current one.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct ActiveTransition {
+    outgoing_index: usize,
+    transition: TransitionDto,
+    /// How far into the transition the current slide was when it was shown.
+    elapsed: u32,
+}
+
+impl ActiveTransition {
+    /// CSS animation of the incoming or outgoing entry, see `public/styles.css`.
+    fn animation(&self, incoming: bool) -> Option<String> {
+        let name = match (self.transition.transition_type, incoming) {
+            (TransitionType::Cut, _) => return None,
+            (TransitionType::Crossfade, true) => "transition-crossfade-in",
+            // the incoming entry fades in on top of the outgoing one
+            (TransitionType::Crossfade, false) => return None,
+            (TransitionType::SlideLeft, true) => "transition-slide-left-in",
+            (TransitionType::SlideLeft, false) => "transition-slide-left-out",
+            (TransitionType::SlideUp, true) => "transition-slide-up-in",
+            (TransitionType::SlideUp, false) => "transition-slide-up-out",
+        };
+        // a negative delay starts the animation part of the way through
+        Some(format!(
+            "{name} {}ms ease-in-out -{}ms both",
+            self.transition.duration, self.elapsed
+        ))
+    }
+}
+
+/// Content of a single feed entry, which is only visible while it's the current one.
+#[component]
+fn FeedEntry(
+    entry: FeedEntryDto,
+    is_current: Signal<bool>,
+    /// The entry is still shown while the transition to the next one is in progress.
+    is_outgoing: Signal<bool>,
+    animation: Signal<Option<String>>,
+    /// When the current turn of the entry started, in milliseconds since the Unix epoch.
+    started_at: Signal<Option<i64>>,
+    clock: ServerClock,
+) -> impl IntoView {
+    let is_hidden = move || !is_current.get() && !is_outgoing.get();
+
+    match entry.content_type {
+        ContentType::Image => view! {
+            <img
+                class="absolute inset-0 object-contain h-screen w-screen"
+                class:invisible=is_hidden
+                class:z-10=is_current
+                style:animation=animation
+                src=entry.url
+            />
+        }
+        .into_any(),
+        ContentType::Video => {
+            let video = NodeRef::<Video>::new();
+            // Whether the video can be played to the end without stopping to buffer
+            let ready = RwSignal::new(false);
+
+            // Videos are buffered while hidden, and only start playing once it's their turn and
+            // they're ready. If that's late, they skip ahead to stay in sync with the other screens.
+            Effect::new(move || {
+                let Some(video) = video.get() else {
+                    return;
+                };
+                match started_at.get() {
+                    Some(started_at) if ready.get() => {
+                        let elapsed_ms = (clock.now().timestamp_millis() - started_at).max(0);
+                        video.set_current_time(elapsed_ms as f64 / 1000.0);
+                        let _ = video.play();
+                    }
+                    _ => {
+                        let _ = video.pause();
+                    }
+                }
+            });
+
+            view! {
+                <video
+                    muted
+                    preload="auto"
+                    playsinline
+                    class="absolute inset-0 object-contain h-screen w-screen"
+                    class:invisible=is_hidden
+                    class:z-10=is_current
+                    style:animation=animation
+                    src=entry.url
+                    node_ref=video
+                    on:loadedmetadata:target=move |ev| ev.target().set_muted(true)
+                    // only set once, since seeking makes the video fire these events again
+                    on:canplaythrough=move |_| {
+                        if !ready.get_untracked() {
+                            ready.set(true);
                         }
-                        ContentType::Html => {
-                            view! {
-                                <iframe
-                                    sandbox="allow-scripts allow-same-origin"
-                                    class="object-contain h-screen w-screen"
-                                    src=&entry.url
-                                />
-                            }
-                                .into_any()
+                    }
+                    on:progress:target=move |ev| {
+                        let target = ev.target();
+                        let buffered = target.buffered();
+                        let ranges: Vec<(f64, f64)> = (0..buffered.length())
+                            .filter_map(|i| Some((buffered.start(i).ok()?, buffered.end(i).ok()?)))
+                            .collect();
+                        if !ready.get_untracked() && is_fully_buffered(&ranges, target.duration()) {
+                            ready.set(true);
                         }
                     }
-                })
-        }}
+                />
+            }
+            .into_any()
+        }
+        ContentType::Text => view! {
+            <div
+                class="absolute inset-0 h-screen w-screen"
+                class:invisible=is_hidden
+                class:z-10=is_current
+                style:animation=animation
+            >
+                <TextSlide text=entry.text.unwrap_or_default() background=entry.url />
+            </div>
+        }
+        .into_any(),
+        ContentType::Html | ContentType::Url => view! {
+            <iframe
+                sandbox="allow-scripts allow-same-origin"
+                class="absolute inset-0 object-contain h-screen w-screen"
+                class:invisible=is_hidden
+                class:z-10=is_current
+                style:animation=animation
+                src=entry.url
+            />
+        }
+        .into_any(),
     }
-    .into_any()
+}
+
+/// Get the entries whose slide group hasn't ended at `now`.
+fn active_entries(feed: &[FeedEntryDto], now: DateTime<Utc>) -> Vec<FeedEntryDto> {
+    feed.iter()
+        .filter(|entry| entry.end_date.is_none_or(|end_date| end_date > now))
+        .cloned()
+        .collect()
+}
+
+/// Videos whose buffered ranges end at most this close to their end are considered fully buffered,
+/// as the reported ranges aren't exact.
+const BUFFERED_TOLERANCE_SECS: f64 = 0.1;
+
+/// Whether the buffered time ranges (in seconds) of a video cover all of its `duration`.
+fn is_fully_buffered(ranges: &[(f64, f64)], duration: f64) -> bool {
+    duration.is_finite()
+        && ranges
+            .iter()
+            .any(|(start, end)| *start <= 0.0 && *end >= duration - BUFFERED_TOLERANCE_SECS)
+}
+
+/// Entry of the feed to show for its duration, starting at `started_at`, instead of the one given
+/// by the rotation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SlideJump {
+    pub index: usize,
+    pub started_at: DateTime<Utc>,
+}
+
+impl SlideJump {
+    /// Is `None` once the entry has been shown for its duration, or if there is no such entry.
+    fn next_slide(&self, feed: &[FeedEntryDto], now: DateTime<Utc>) -> Option<NextSlide> {
+        let duration = feed.get(self.index)?.duration;
+        let elapsed: u32 = (now - self.started_at).num_milliseconds().try_into().ok()?;
+        let remaining: u32 = (duration - elapsed as i32).try_into().ok()?;
+        if remaining == 0 {
+            return None;
+        }
+        Some(NextSlide {
+            index: self.index,
+            elapsed,
+            remaining,
+        })
+    }
+}
+
+/// The slide which should currently be displayed.
+struct NextSlide {
+    index: usize,
+    /// Milliseconds since the slide started being displayed.
+    elapsed: u32,
+    /// Milliseconds until the next slide should be displayed.
+    remaining: u32,
 }
 
 /// Get a feed and calculate the slide that should be displayed, along with how long until the next
 /// slide should be displayed.
 ///
 /// Slides are aligned with time, and this calculated is therefore deterministic for a given
-/// timestamp (this function automatically gets the current time).
-fn calculate_next_slide(feed: &[FeedEntryDto]) -> Option<(u32, &FeedEntryDto)> {
+/// timestamp, which should be the server's time so that all screens agree.
+fn calculate_next_slide(feed: &[FeedEntryDto], now: DateTime<Utc>) -> Option<NextSlide> {
     let total_time: i32 = Some(feed.iter().map(|e| e.duration).sum::<i32>()).filter(|t| *t > 0)?;
 
-    let now = Utc::now().timestamp_millis();
+    let now = now.timestamp_millis();
     let offset = (now % total_time as i64) as i32;
 
-    let (ellapsed_duration, entry) = feed
+    let (index, ellapsed_duration) = feed
         .iter()
         .scan(0, |ellapsed_duration, entry| {
             *ellapsed_duration += entry.duration;
-            Some((*ellapsed_duration, entry))
+            Some(*ellapsed_duration)
         })
-        .find(|(ellapsed_duration, _)| *ellapsed_duration > offset)?;
+        .enumerate()
+        .find(|(_, ellapsed_duration)| *ellapsed_duration > offset)?;
+
+    let remaining: u32 = (ellapsed_duration - offset).try_into().ok()?;
+    let elapsed: u32 = (feed[index].duration - remaining as i32).try_into().ok()?;
+    Some(NextSlide {
+        index,
+        elapsed,
+        remaining,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, Utc};
+    use common::dtos::{ContentType, FeedEntryDto, TransitionDto};
+
+    use super::{active_entries, is_fully_buffered};
 
-    let remaining_time: u32 = (ellapsed_duration - offset).try_into().ok()?;
-    Some((remaining_time, entry))
+    #[test]
+    fn ended_entries_are_skipped() {
+        let now = Utc::now();
+        let entry = FeedEntryDto {
+            content_type: ContentType::Image,
+            url: "https://example.com/a.png".to_string(),
+            duration: 10_000,
+            end_date: None,
+            transition: TransitionDto::default(),
+            text: None,
+        };
+        let ending = FeedEntryDto {
+            end_date: Some(now + TimeDelta::minutes(1)),
+            ..entry.clone()
+        };
+        let ended = FeedEntryDto {
+            end_date: Some(now),
+            ..entry.clone()
+        };
+
+        assert_eq!(
+            active_entries(&[entry.clone(), ended, ending.clone()], now),
+            vec![entry, ending]
+        );
+    }
+
+    #[test]
+    fn fully_buffered_videos() {
+        assert!(is_fully_buffered(&[(0.0, 12.0)], 12.0));
+        assert!(is_fully_buffered(&[(0.0, 11.95)], 12.0));
+        // the start is still missing
+        assert!(!is_fully_buffered(&[(0.5, 12.0)], 12.0));
+        assert!(!is_fully_buffered(&[(0.0, 4.0), (6.0, 12.0)], 12.0));
+        assert!(!is_fully_buffered(&[], 12.0));
+        // the duration is unknown until the metadata has loaded
+        assert!(!is_fully_buffered(&[(0.0, 12.0)], f64::NAN));
+    }
 }
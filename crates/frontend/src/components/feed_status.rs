@@ -0,0 +1,19 @@
+use icondata as i;
+use leptos::prelude::*;
+use leptos_icons::Icon;
+
+/// Small indicator in the corner of a screen, shown while its feed can't be updated, e.g. because
+/// the server is unreachable. The slideshow keeps showing the last feed in the meantime.
+#[component]
+pub fn FeedStatus(#[prop(into)] problem: Signal<Option<String>>) -> impl IntoView {
+    move || {
+        problem.get().map(|problem| {
+            view! {
+                <div class="fixed bottom-2 right-2 z-30 flex items-center gap-2 rounded bg-black/60 px-3 py-1 text-sm text-white">
+                    <Icon icon=i::MdiAlertCircleOutline />
+                    {problem}
+                </div>
+            }
+        })
+    }
+}
@@ -1,13 +1,17 @@
 pub mod alert;
 pub mod content;
 pub mod dialog;
+pub mod emergency_message;
 pub mod error;
 pub mod feed;
+pub mod feed_status;
 pub mod layout;
 pub mod owner_select;
 pub mod rules_body;
 pub mod slide;
 pub mod slide_group;
 pub mod start_end_date_input;
+pub mod text_slide;
 pub mod topbar;
+pub mod transition_input;
 pub mod utils;
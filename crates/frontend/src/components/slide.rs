@@ -1,11 +1,17 @@
+use common::dtos::ContentDto;
 use leptos::prelude::*;
 use reactive_stores::{Field, Store};
+use web_sys::File;
 
 use crate::{
-    components::{content::ContentItem, dialog::Dialog, rules_body::RulesBody},
+    api,
+    components::{content::ContentItem, dialog::Dialog, error::ErrorList, rules_body::RulesBody},
     context::ScreenContext,
-    utils::edit_slide_group::{
-        EditSlide, EditSlideGroup, EditSlideGroupStoreFields, EditSlideStoreFields,
+    utils::{
+        datetime::fmt_duration_ms,
+        edit_slide_group::{
+            EditSlide, EditSlideGroup, EditSlideGroupStoreFields, EditSlideStoreFields,
+        },
     },
 };
 
@@ -14,7 +20,7 @@ pub fn SlideList(
     #[prop(into)] slide_group: Store<EditSlideGroup>,
     #[prop(into)] editable: Signal<bool>,
 ) -> impl IntoView {
-    let add_slide = move || {
+    let add_slide = move |content: Vec<ContentDto>| {
         slide_group.update(|slide_group| {
             let max_position = slide_group
                 .slides
@@ -25,10 +31,12 @@ pub fn SlideList(
             slide_group.slides.push(EditSlide {
                 existing: None,
                 position: max_position + 1,
-                content: Vec::new(),
+                duration: None,
+                content,
             });
         });
     };
+    let is_pdf_dialog_open = RwSignal::new(false);
 
     view! {
         <Show when=move || editable.get()>
@@ -69,18 +77,132 @@ pub fn SlideList(
         {move || {
             view! {
                 <Show when=move || editable.get()>
-                    <button
-                        class="btn"
-                        on:click=move |_| {
-                            add_slide();
-                        }
-                    >
-                        "Add Slide"
-                    </button>
+                    <div class="flex gap-3">
+                        <button
+                            class="btn"
+                            on:click=move |_| {
+                                add_slide(Vec::new());
+                            }
+                        >
+                            "Add Slide"
+                        </button>
+                        <button class="btn" on:click=move |_| is_pdf_dialog_open.set(true)>
+                            "Add Slides from PDF"
+                        </button>
+                    </div>
                 </Show>
             }
                 .into_any()
         }}
+        <PdfUploadDialog
+            open=is_pdf_dialog_open
+            on_submit=move |pages| {
+                for page in pages {
+                    add_slide(vec![page]);
+                }
+            }
+        />
+    }
+    .into_any()
+}
+
+/// Dialog for uploading a PDF, whose pages are each shown on a new slide on the chosen screen.
+#[component]
+fn PdfUploadDialog(
+    open: RwSignal<bool>,
+    // Function which is called with the content of every page, in order.
+    on_submit: impl Fn(Vec<ContentDto>) + 'static,
+) -> impl IntoView {
+    let screens = use_context::<ScreenContext>()
+        .expect("expected screen context")
+        .screens;
+    let input_ref = NodeRef::new();
+    let screen = RwSignal::new(None::<i32>);
+
+    let upload_action = Action::new_local(move |(screen, file): &(i32, File)| {
+        let screen = *screen;
+        let file = file.clone();
+        async move { api::upload_pdf_content(screen, &file).await }
+    });
+
+    let is_submitting = upload_action.pending();
+    let response = move || upload_action.value().get();
+    Effect::new(move || {
+        if let Some(Ok(pages)) = response() {
+            open.set(false);
+            // Make sure that the caller doesn't accidentally subscribe this effect to other
+            // dependencies.
+            untrack(|| on_submit(pages));
+        }
+    });
+
+    view! {
+        <Dialog open=open>
+            <div class="card space-y-6 p-4">
+                <form on:submit=move |ev| {
+                    ev.prevent_default();
+                    let screen = screen
+                        .get_untracked()
+                        .or_else(|| screens.get_untracked().first().map(|screen| screen.id));
+                    if let Some((screen, file)) = screen
+                        .zip(
+                            input_ref
+                                .get()
+                                .and_then(|input| { input.files() })
+                                .and_then(|filelist| filelist.item(0)),
+                        )
+                    {
+                        upload_action.dispatch_local((screen, file));
+                    }
+                }>
+                    <fieldset disabled=is_submitting class="w-2xs space-y-4">
+                        <p>"Every page of the PDF is shown on a new slide."</p>
+                        <label class="select">
+                            <span class="label">"Screen"</span>
+                            <select on:change:target=move |ev| {
+                                screen.set(ev.target().value().parse().ok());
+                            }>
+                                <For
+                                    each=move || screens.get()
+                                    key=|screen| screen.id
+                                    children=move |option| {
+                                        view! {
+                                            <option
+                                                value=option.id
+                                                selected=move || screen.get() == Some(option.id)
+                                            >
+                                                {option.name}
+                                            </option>
+                                        }
+                                    }
+                                />
+                            </select>
+                        </label>
+                        <div class="overflow-hidden">
+                            <input
+                                class="rounded-sm input"
+                                node_ref=input_ref
+                                type="file"
+                                accept="application/pdf"
+                                required="true"
+                            />
+                        </div>
+                        <div class="mt-6 flex gap-3">
+                            <button class="btn" type="submit">
+                                "Upload"
+                            </button>
+                            <button class="btn" type="button" on:click=move |_| open.set(false)>
+                                "Cancel"
+                            </button>
+                        </div>
+                    </fieldset>
+                </form>
+
+                <ErrorBoundary fallback=|errors| {
+                    view! { <ErrorList errors=errors /> }.into_any()
+                }>{move || response().map(|r| r.map(|_| ()))}</ErrorBoundary>
+            </div>
+        </Dialog>
     }
     .into_any()
 }
@@ -111,7 +233,6 @@ fn SlideRow(
                                     slide.content.iter().find(|c| c.screen == screen.id).cloned()
                                 })
                         });
-                        let screen_copy = screen.clone();
                         view! {
                             <ContentItem
                                 attr:class="w-[18rem] grow"
@@ -121,9 +242,11 @@ fn SlideRow(
                                     let slide_content = slide.content();
                                     slide_content
                                         .update(|slide_content| {
+                                            // content of other screens is uploaded too when
+                                            // spanning an image across all screens
                                             match slide_content
                                                 .iter_mut()
-                                                .find(move |content| content.screen == screen_copy.id)
+                                                .find(|content| content.screen == new_content.screen)
                                             {
                                                 Some(content) => *content = new_content,
                                                 None => slide_content.push(new_content),
@@ -131,6 +254,7 @@ fn SlideRow(
                                         });
                                 }
                                 editable
+                                allow_span=true
                             />
                         }
                             .into_any()
@@ -139,10 +263,23 @@ fn SlideRow(
             </div>
             {move || {
                 view! {
-                    <Show when=move || editable.get()>
-                        <div class="flex flex-row justify-center md:justify-start">
+                    <Show
+                        when=move || editable.get()
+                        fallback=move || {
+                            view! {
+                                <Show when=move || slide.duration().get().is_some()>
+                                    <p class="text-sm text-current/60 my-3">
+                                        "Shown for "
+                                        {move || slide.duration().get().map(fmt_duration_ms)}
+                                    </p>
+                                </Show>
+                            }
+                        }
+                    >
+                        <div class="flex flex-row flex-wrap gap-3 items-center justify-center md:justify-start my-3">
+                            <SlideDurationInput duration=slide.duration() />
                             <button
-                                class="btn btn-soft btn-choose btn-error my-3"
+                                class="btn btn-soft btn-choose btn-error"
                                 on:click=move |_| is_delete_dialog_open.set(true)
                             >
                                 "Delete Slide"
@@ -157,6 +294,39 @@ fn SlideRow(
     .into_any()
 }
 
+/// Input for how long a slide is shown, in seconds. Leaving it empty uses the default duration.
+#[component]
+fn SlideDurationInput(#[prop(into)] duration: Field<Option<i32>>) -> impl IntoView {
+    view! {
+        <label class="input w-fit">
+            <span class="label">"Duration"</span>
+            <input
+                class="w-20"
+                type="number"
+                min=1
+                step="any"
+                placeholder="Default"
+                prop:value=move || {
+                    duration
+                        .get()
+                        .map(|ms| (ms as f64 / 1000.0).to_string())
+                        .unwrap_or_default()
+                }
+                on:change:target=move |ev| {
+                    let seconds = ev.target().value().parse::<f64>().ok();
+                    duration
+                        .set(
+                            seconds
+                                .map(|seconds| (seconds * 1000.0).round() as i32)
+                                .filter(|ms| *ms > 0),
+                        );
+                }
+            />
+            <span class="label">"s"</span>
+        </label>
+    }
+}
+
 #[component]
 pub fn DeleteDialog(open: RwSignal<bool>, on_delete: impl Fn() + 'static + Send) -> impl IntoView {
     view! {
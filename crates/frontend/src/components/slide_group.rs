@@ -1,19 +1,25 @@
 use chrono::Utc;
-use common::dtos::{EditSlideGroupDto, OwnerDto, UserInfoDto};
+use common::dtos::{EditSlideGroupDto, OwnerDto, UserInfoDto, MAX_PRIORITY};
 use icondata as i;
 use leptos::prelude::*;
 use leptos_icons::Icon;
-use reactive_stores::Store;
+use reactive_stores::{Field, Store};
 
 use crate::{
     api::{self, AppError},
     components::{
-        alert::Alert, dialog::Dialog, error::ErrorList, owner_select::OwnerSelect,
-        slide::SlideList, start_end_date_input::StartEndDateInput,
+        alert::Alert,
+        dialog::Dialog,
+        error::ErrorList,
+        owner_select::OwnerSelect,
+        slide::SlideList,
+        start_end_date_input::StartEndDateInput,
+        transition_input::{fmt_transition, TransitionInput},
     },
+    context::ScreenContext,
     utils::{
         bool::fmt_if,
-        datetime::{fmt_datetime, fmt_datetime_opt},
+        datetime::{fmt_datetime, fmt_datetime_opt, fmt_recurrence},
         edit_slide_group::{EditSlideGroup, EditSlideGroupStoreFields},
     },
 };
@@ -197,6 +203,23 @@ pub fn SlideGroup(
 
 #[component]
 fn SlideGroupPropertiesDisplay(#[prop(into)] slide_group: Store<EditSlideGroup>) -> impl IntoView {
+    let all_screens = use_context::<ScreenContext>()
+        .expect("expected screen context")
+        .screens;
+    let screen_names = move || {
+        let screens = slide_group.get().screens;
+        if screens.is_empty() {
+            return "All screens".to_string();
+        }
+        all_screens
+            .get()
+            .into_iter()
+            .filter(|screen| screens.contains(&screen.id))
+            .map(|screen| screen.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
     view! {
         {move || match slide_group.get().created_by.clone() {
             common::dtos::OwnerDto::User(username) => {
@@ -207,10 +230,13 @@ fn SlideGroupPropertiesDisplay(#[prop(into)] slide_group: Store<EditSlideGroup>)
             }
         }}
         <PropertyDisplay icon=Signal::derive(move || fmt_if(
-            slide_group.get().priority > 0,
+            slide_group.get().takeover,
             i::MdiPin,
             i::MdiPinOff,
-        ))>{move || fmt_if(slide_group.get().priority > 0, "Pinned", "Unpinned")}</PropertyDisplay>
+        ))>{move || fmt_if(slide_group.get().takeover, "Pinned", "Unpinned")}</PropertyDisplay>
+        <PropertyDisplay icon=i::MdiSortNumericDescending>
+            {move || format!("Priority {}", slide_group.get().priority)}
+        </PropertyDisplay>
         <PropertyDisplay icon=Signal::derive(move || fmt_if(
             slide_group.get().hidden,
             i::MdiEyeOff,
@@ -218,6 +244,10 @@ fn SlideGroupPropertiesDisplay(#[prop(into)] slide_group: Store<EditSlideGroup>)
         ))>
             {move || fmt_if(slide_group.get().hidden, "Hidden from others", "Shown to everyone")}
         </PropertyDisplay>
+        <PropertyDisplay icon=i::MdiMonitorMultiple>{screen_names}</PropertyDisplay>
+        <PropertyDisplay icon=i::MdiTransition>
+            {move || fmt_transition(slide_group.get().transition)}
+        </PropertyDisplay>
         <PropertyDisplay icon=i::MdiClock class="col-span-full">
             <div class="flex gap-2 items-center">
                 {move || fmt_datetime(&slide_group.get().start_date)} <span class="shrink-0">
@@ -225,6 +255,18 @@ fn SlideGroupPropertiesDisplay(#[prop(into)] slide_group: Store<EditSlideGroup>)
                 </span> {move || fmt_datetime_opt(slide_group.get().end_date.as_ref(), "Forever")}
             </div>
         </PropertyDisplay>
+        {move || {
+            slide_group
+                .get()
+                .recurrence
+                .map(|recurrence| {
+                    view! {
+                        <PropertyDisplay icon=i::MdiCalendarSync class="col-span-full">
+                            {fmt_recurrence(&recurrence)}
+                        </PropertyDisplay>
+                    }
+                })
+        }}
     }
     .into_any()
 }
@@ -236,7 +278,10 @@ fn SlideGroupPropertiesEditor(
 ) -> impl IntoView {
     let owner = slide_group.created_by();
     let priority = slide_group.priority();
+    let takeover = slide_group.takeover();
     let hidden = slide_group.hidden();
+    let screens = slide_group.screens();
+    let transition = slide_group.transition();
 
     let start_date = RwSignal::new(slide_group.get_untracked().start_date);
     let end_date = RwSignal::new(
@@ -246,6 +291,7 @@ fn SlideGroupPropertiesEditor(
             .map(|date| (true, date))
             .unwrap_or_else(|| (false, Utc::now())),
     );
+    let recurrence = RwSignal::new(slide_group.get_untracked().recurrence);
 
     Effect::new(move || {
         // This is not a good way to achieve reactivity, but I'm not sure how to do it better.
@@ -254,6 +300,7 @@ fn SlideGroupPropertiesEditor(
             (true, date) => Some(date),
             (false, _) => None,
         });
+        slide_group.recurrence().set(recurrence.get());
     });
 
     view! {
@@ -265,9 +312,9 @@ fn SlideGroupPropertiesEditor(
             <input
                 type="checkbox"
                 class="checkbox"
-                prop:checked=move || { priority.get() > 0 }
+                prop:checked=move || takeover.get()
                 on:input:target=move |ev| {
-                    priority.set(if ev.target().checked() { 1 } else { 0 });
+                    takeover.set(ev.target().checked());
                 }
                 disabled=disabled
             />
@@ -279,6 +326,29 @@ fn SlideGroupPropertiesEditor(
                 <Icon icon=i::MdiInformationOutline width="1.1em" height="1.1em" />
             </span>
         </label>
+        <label class="input">
+            <span class="label">
+                "Priority"
+                <span
+                    class="tooltip"
+                    data-tip="Slide groups with higher priority are shown more often than others."
+                >
+                    <Icon icon=i::MdiInformationOutline width="1.1em" height="1.1em" />
+                </span>
+            </span>
+            <input
+                type="number"
+                min=0
+                max=MAX_PRIORITY
+                prop:value=move || priority.get()
+                on:change:target=move |ev| {
+                    if let Ok(value) = ev.target().value().parse::<i32>() {
+                        priority.set(value.clamp(0, MAX_PRIORITY));
+                    }
+                }
+                disabled=disabled
+            />
+        </label>
         <label class="label">
             <input
                 type="checkbox"
@@ -295,16 +365,87 @@ fn SlideGroupPropertiesEditor(
             </span>
         </label>
 
+        <TransitionInput transition disabled />
+
+        <ScreenSelect screens disabled />
+
         <StartEndDateInput
             start_date
             end_date
             disable_end_date_removal_reason=None
+            recurrence
             class="col-span-full"
             disabled=disabled
         />
     }
 }
 
+/// Checkboxes for the screens a slide group is shown on, where an empty selection means all
+/// screens.
+#[component]
+fn ScreenSelect(#[prop(into)] screens: Field<Vec<i32>>, disabled: Signal<bool>) -> impl IntoView {
+    let all_screens = use_context::<ScreenContext>()
+        .expect("expected screen context")
+        .screens;
+
+    let toggle_screen = move |id: i32| {
+        let mut selected = screens.get();
+        if selected.is_empty() {
+            selected = all_screens.get().iter().map(|screen| screen.id).collect();
+        }
+        if let Some(index) = selected.iter().position(|screen| *screen == id) {
+            selected.remove(index);
+        } else {
+            selected.push(id);
+            selected.sort();
+        }
+
+        // at least one screen must be selected, and selecting every screen is the same as none
+        if selected.is_empty() {
+            return;
+        }
+        if selected.len() == all_screens.get().len() {
+            selected.clear();
+        }
+        screens.set(selected);
+    };
+
+    view! {
+        <fieldset class="col-span-full flex flex-wrap items-center gap-x-4 gap-y-2">
+            <span class="label">
+                "Screens"
+                <span
+                    class="tooltip"
+                    data-tip="Only show this slide group on the selected screens."
+                >
+                    <Icon icon=i::MdiInformationOutline width="1.1em" height="1.1em" />
+                </span>
+            </span>
+            <For
+                each=move || all_screens.get()
+                key=|screen| screen.id
+                children=move |screen| {
+                    view! {
+                        <label class="label">
+                            <input
+                                type="checkbox"
+                                class="checkbox"
+                                prop:checked=move || {
+                                    let selected = screens.get();
+                                    selected.is_empty() || selected.contains(&screen.id)
+                                }
+                                on:input=move |_| toggle_screen(screen.id)
+                                disabled=disabled
+                            />
+                            {screen.name.clone()}
+                        </label>
+                    }
+                }
+            />
+        </fieldset>
+    }
+}
+
 #[component]
 fn PropertyDisplay(
     #[prop(into)] icon: Signal<icondata_core::Icon>,
@@ -1,12 +1,15 @@
 //! Widget which includes two date inputs, meant for setting the start and end dates of a slide
-//! group.
+//! group, optionally along with a weekly schedule within those dates.
 
 use crate::{
     components::utils::{If, Otherwise, Then},
-    utils::datetime::{datetime_to_input, input_to_datetime},
+    utils::datetime::{
+        datetime_to_input, fmt_weekday, input_to_datetime, input_to_time, time_to_input,
+    },
 };
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveTime, Utc, Weekday};
+use common::dtos::RecurrenceDto;
 use icondata as i;
 use leptos::{html::Div, prelude::*};
 use leptos_icons::Icon;
@@ -16,6 +19,9 @@ pub fn StartEndDateInput(
     start_date: RwSignal<DateTime<Utc>>,
     end_date: RwSignal<(bool, DateTime<Utc>)>,
     disable_end_date_removal_reason: Option<&'static str>,
+    /// Weekly schedule, which can only be edited if this is set.
+    #[prop(optional)]
+    recurrence: Option<RwSignal<Option<RecurrenceDto>>>,
     #[prop(into, default = "".into())] class: Signal<&'static str>,
     #[prop(into, optional)] disabled: Signal<bool>,
 ) -> impl IntoView {
@@ -123,19 +129,173 @@ pub fn StartEndDateInput(
                     </If>
                 </div>
             </div>
+            {recurrence.map(|recurrence| view! { <RecurrenceInput recurrence disabled /> })}
             <div class="flex justify-between items-top mt-3">
                 <p class="text-sm/6 text-current/60">Dates are in Swedish time</p>
-                <Show when=move || disable_end_date_removal_reason.is_none()>
-                    <button
-                        class="btn"
-                        type="button"
-                        on:click=move |_| { end_date.update(|(enabled, _)| *enabled = !*enabled) }
-                        disabled=disabled
-                    >
-                        {end_date_text}
-                    </button>
-                </Show>
+                <div class="flex flex-wrap justify-end gap-2">
+                    {recurrence
+                        .map(|recurrence| {
+                            view! {
+                                <button
+                                    class="btn"
+                                    type="button"
+                                    on:click=move |_| {
+                                        recurrence
+                                            .update(|recurrence| {
+                                                *recurrence = match recurrence {
+                                                    Some(_) => None,
+                                                    None => Some(default_recurrence()),
+                                                };
+                                            })
+                                    }
+                                    disabled=disabled
+                                >
+                                    {move || {
+                                        if recurrence.get().is_some() {
+                                            "Remove weekly schedule"
+                                        } else {
+                                            "Repeat weekly"
+                                        }
+                                    }}
+                                </button>
+                            }
+                        })}
+                    <Show when=move || disable_end_date_removal_reason.is_none()>
+                        <button
+                            class="btn"
+                            type="button"
+                            on:click=move |_| {
+                                end_date.update(|(enabled, _)| *enabled = !*enabled)
+                            }
+                            disabled=disabled
+                        >
+                            {end_date_text}
+                        </button>
+                    </Show>
+                </div>
             </div>
         </div>
     }
 }
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Every day during office hours.
+fn default_recurrence() -> RecurrenceDto {
+    RecurrenceDto {
+        weekdays: WEEKDAYS.to_vec(),
+        start_time: NaiveTime::from_hms_opt(8, 0, 0).expect("08:00 is a time"),
+        end_time: NaiveTime::from_hms_opt(17, 0, 0).expect("17:00 is a time"),
+    }
+}
+
+/// Weekday toggles and a daily time window, shown while a weekly schedule is set.
+#[component]
+fn RecurrenceInput(
+    recurrence: RwSignal<Option<RecurrenceDto>>,
+    disabled: Signal<bool>,
+) -> impl IntoView {
+    let toggle_weekday = move |day: Weekday| {
+        recurrence.update(|recurrence| {
+            let Some(recurrence) = recurrence else {
+                return;
+            };
+            if let Some(index) = recurrence.weekdays.iter().position(|d| *d == day) {
+                recurrence.weekdays.remove(index);
+            } else {
+                recurrence.weekdays.push(day);
+                recurrence
+                    .weekdays
+                    .sort_by_key(|d| d.num_days_from_monday());
+            }
+        })
+    };
+    let has_weekday = move |day: Weekday| {
+        recurrence
+            .get()
+            .is_some_and(|recurrence| recurrence.weekdays.contains(&day))
+    };
+
+    view! {
+        <Show when=move || recurrence.get().is_some()>
+            <div class="flex flex-wrap items-center gap-4 mt-4">
+                <div class="join">
+                    {WEEKDAYS
+                        .into_iter()
+                        .map(|day| {
+                            view! {
+                                <button
+                                    class="btn join-item"
+                                    class:btn-primary=move || has_weekday(day)
+                                    type="button"
+                                    on:click=move |_| toggle_weekday(day)
+                                    disabled=disabled
+                                >
+                                    {fmt_weekday(day)}
+                                </button>
+                            }
+                        })
+                        .collect_view()}
+                </div>
+                <div class="flex items-center gap-2">
+                    <label class="input w-auto">
+                        <span class="label">"From"</span>
+                        <input
+                            type="time"
+                            step=60
+                            prop:value=move || {
+                                recurrence
+                                    .get()
+                                    .map(|recurrence| time_to_input(&recurrence.start_time))
+                                    .unwrap_or_default()
+                            }
+                            on:change:target=move |ev| {
+                                if let Some(time) = input_to_time(&ev.target().value()) {
+                                    recurrence
+                                        .update(|recurrence| {
+                                            if let Some(recurrence) = recurrence {
+                                                recurrence.start_time = time;
+                                            }
+                                        });
+                                }
+                            }
+                            disabled=disabled
+                        />
+                    </label>
+                    <label class="input w-auto">
+                        <span class="label">"To"</span>
+                        <input
+                            type="time"
+                            step=60
+                            prop:value=move || {
+                                recurrence
+                                    .get()
+                                    .map(|recurrence| time_to_input(&recurrence.end_time))
+                                    .unwrap_or_default()
+                            }
+                            on:change:target=move |ev| {
+                                if let Some(time) = input_to_time(&ev.target().value()) {
+                                    recurrence
+                                        .update(|recurrence| {
+                                            if let Some(recurrence) = recurrence {
+                                                recurrence.end_time = time;
+                                            }
+                                        });
+                                }
+                            }
+                            disabled=disabled
+                        />
+                    </label>
+                </div>
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,34 @@
+use common::dtos::TextContentDto;
+use leptos::prelude::*;
+
+use crate::utils::markdown::{render_markdown, text_font_size};
+
+/// Markdown shown on a slide, filling its container. Sizes are relative to the container, so that
+/// previews look exactly like the screens.
+#[component]
+pub fn TextSlide(
+    text: TextContentDto,
+    /// Url of the background image, or empty if there is none.
+    #[prop(into)]
+    background: String,
+) -> impl IntoView {
+    let font_size = text_font_size(&text.markdown);
+    let background_image = (!background.is_empty()).then(|| format!("url(\"{background}\")"));
+
+    // the font size is relative to the outer element, which must be a container for that
+    view! {
+        <div
+            class="h-full w-full bg-cover bg-center"
+            style:container-type="size"
+            style:background-color=text.background_color
+            style:background-image=background_image
+            style:color=text.text_color
+        >
+            <div
+                class="text-slide h-full flex flex-col justify-center overflow-hidden"
+                style:font-size=format!("min({font_size}cqh, {}cqw)", font_size * 9.0 / 16.0)
+                inner_html=render_markdown(&text.markdown)
+            />
+        </div>
+    }
+}
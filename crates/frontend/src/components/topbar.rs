@@ -27,6 +27,14 @@ pub fn Topbar() -> impl IntoView {
                                     Ok(
                                         view! {
                                             <p>
+                                                <a href="/preview" class="btn text-base mr-2">
+                                                    "Preview"
+                                                </a>
+                                                <Show when=move || data.is_admin>
+                                                    <a href="/screens" class="btn text-base mr-2">
+                                                        "Screens"
+                                                    </a>
+                                                </Show>
                                                 "Welcome, "{data.username}
                                                 <a
                                                     href="/auth/logout"
@@ -0,0 +1,141 @@
+use common::dtos::{TransitionDto, TransitionType};
+use leptos::prelude::*;
+use reactive_stores::Field;
+
+const TRANSITION_TYPES: [TransitionType; 4] = [
+    TransitionType::Cut,
+    TransitionType::Crossfade,
+    TransitionType::SlideLeft,
+    TransitionType::SlideUp,
+];
+
+/// Used when switching from a cut to a transition which has a duration.
+const DEFAULT_DURATION_MS: i32 = 500;
+
+/// Select for how slides are transitioned to, where `None` means the default of each screen.
+///
+/// The screen default can't be selected if `required` is set, as for the default itself.
+#[component]
+pub fn TransitionInput(
+    #[prop(into)] transition: Field<Option<TransitionDto>>,
+    #[prop(into, optional)] disabled: Signal<bool>,
+    #[prop(optional)] required: bool,
+) -> impl IntoView {
+    let has_duration = move || {
+        transition
+            .get()
+            .is_some_and(|transition| transition.transition_type != TransitionType::Cut)
+    };
+
+    view! {
+        <label class="select">
+            <span class="label">"Transition"</span>
+            <select
+                prop:value=move || {
+                    transition
+                        .get()
+                        .and_then(|transition| {
+                            TRANSITION_TYPES
+                                .iter()
+                                .position(|element| *element == transition.transition_type)
+                        })
+                        .map(|index| index.to_string())
+                        .unwrap_or_default()
+                }
+                on:change:target=move |ev| {
+                    let transition_type = ev
+                        .target()
+                        .value()
+                        .parse::<usize>()
+                        .ok()
+                        .map(|index| TRANSITION_TYPES[index]);
+                    transition
+                        .update(|transition| {
+                            *transition = transition_type
+                                .map(|transition_type| {
+                                    let duration = match transition_type {
+                                        TransitionType::Cut => 0,
+                                        _ => {
+                                            transition
+                                                .map(|transition| transition.duration)
+                                                .filter(|duration| *duration > 0)
+                                                .unwrap_or(DEFAULT_DURATION_MS)
+                                        }
+                                    };
+                                    TransitionDto {
+                                        transition_type,
+                                        duration,
+                                    }
+                                });
+                        });
+                }
+                disabled=disabled
+            >
+                <Show when=move || !required>
+                    <option value="">"Screen default"</option>
+                </Show>
+                {TRANSITION_TYPES
+                    .iter()
+                    .enumerate()
+                    .map(|(index, transition_type)| {
+                        view! { <option value=index>{fmt_transition_type(*transition_type)}</option> }
+                    })
+                    .collect_view()}
+            </select>
+        </label>
+        <Show when=has_duration>
+            <label class="input w-fit">
+                <span class="label">"Transition duration"</span>
+                <input
+                    class="w-20"
+                    type="number"
+                    min=0
+                    step="any"
+                    prop:value=move || {
+                        transition
+                            .get()
+                            .map(|transition| (transition.duration as f64 / 1000.0).to_string())
+                            .unwrap_or_default()
+                    }
+                    on:change:target=move |ev| {
+                        if let Ok(seconds) = ev.target().value().parse::<f64>() {
+                            transition
+                                .update(|transition| {
+                                    if let Some(transition) = transition {
+                                        transition.duration = (seconds.max(0.0) * 1000.0).round()
+                                            as i32;
+                                    }
+                                });
+                        }
+                    }
+                    disabled=disabled
+                />
+                <span class="label">"s"</span>
+            </label>
+        </Show>
+    }
+}
+
+pub fn fmt_transition_type(transition_type: TransitionType) -> &'static str {
+    match transition_type {
+        TransitionType::Cut => "Cut",
+        TransitionType::Crossfade => "Crossfade",
+        TransitionType::SlideLeft => "Slide left",
+        TransitionType::SlideUp => "Slide up",
+    }
+}
+
+pub fn fmt_transition(transition: Option<TransitionDto>) -> String {
+    match transition {
+        None => "Screen default transition".to_string(),
+        Some(TransitionDto {
+            transition_type: TransitionType::Cut,
+            ..
+        }) => "Cut".to_string(),
+        Some(transition) => format!(
+            "{} ({} s)",
+            fmt_transition_type(transition.transition_type),
+            transition.duration as f64 / 1000.0
+        ),
+    }
+}
@@ -2,7 +2,10 @@ use components::layout::Layout;
 use leptos::prelude::*;
 use leptos_meta::*;
 use leptos_router::{components::*, path};
-use pages::{create_slide_group::CreateSlideGroup, screen_feed::ScreenFeed};
+use pages::{
+    create_slide_group::CreateSlideGroup, preview::Preview, screen_feed::ScreenFeed,
+    screens::Screens,
+};
 
 // Modules
 mod api;
@@ -37,6 +40,8 @@ pub fn App() -> impl IntoView {
                 <ParentRoute path=path!("/") view=Layout>
                     <Route path=path!("") view=Home />
                     <Route path=path!("new") view=CreateSlideGroup />
+                    <Route path=path!("preview") view=Preview />
+                    <Route path=path!("screens") view=Screens />
                 </ParentRoute>
                 <Route path=path!("/feed/:id") view=ScreenFeed />
             </Routes>
@@ -37,6 +37,7 @@ pub fn CreateSlideGroup() -> impl IntoView {
             .expect("Time is proably before Year 2038")
             .with_timezone(&chrono::Utc),
     ));
+    let recurrence = RwSignal::new(None);
 
     let user_info = use_context::<LocalResource<Result<UserInfoDto, AppError>>>()
         .expect("User info has been provided");
@@ -84,9 +85,13 @@ pub fn CreateSlideGroup() -> impl IntoView {
             api::create_slide_group(&CreateSlideGroupDto {
                 title,
                 priority: 0,
+                takeover: false,
                 hidden: false,
                 start_date: start_date.get(),
                 end_date: Some(end_date.get().1),
+                recurrence: recurrence.get(),
+                transition: None,
+                screens: Vec::new(),
                 owner: selected_owner(),
             })
             .await
@@ -151,6 +156,7 @@ pub fn CreateSlideGroup() -> impl IntoView {
                                 class="mt-2"
                                 start_date=start_date
                                 end_date=end_date
+                                recurrence=recurrence
                                 disable_end_date_removal_reason=Some(
                                     "The end date can be removed after the slide group has been created.",
                                 )
@@ -1,4 +1,6 @@
 pub mod create_slide_group;
 pub mod home;
 pub mod not_found;
+pub mod preview;
 pub mod screen_feed;
+pub mod screens;
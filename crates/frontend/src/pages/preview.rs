@@ -0,0 +1,152 @@
+use crate::{
+    api,
+    components::{error::ErrorList, text_slide::TextSlide, transition_input::fmt_transition},
+    utils::datetime::{datetime_to_input, fmt_datetime, input_to_datetime},
+};
+use chrono::Utc;
+use common::dtos::{ContentType, FeedEntryDto, FeedPreviewDto};
+use leptos::prelude::*;
+
+/// Page showing what will be shown on every screen at a given time, side by side.
+#[component]
+pub fn Preview() -> impl IntoView {
+    let at = RwSignal::new(Utc::now());
+    let include_unpublished = RwSignal::new(false);
+    let previews_resource = LocalResource::new(move || {
+        let at = at.get();
+        let include_unpublished = include_unpublished.get();
+        async move { api::preview_feeds(at, include_unpublished).await }
+    });
+
+    view! {
+        <div class="container m-auto my-4 space-y-8">
+            <div class="card">
+                <div class="card-body">
+                    <h2 class="card-title text-2xl">"Preview"</h2>
+                    <div class="flex flex-wrap items-center gap-4">
+                        <label class="input w-fit">
+                            <span class="label">"Time"</span>
+                            <input
+                                type="datetime-local"
+                                step=60
+                                prop:value=move || datetime_to_input(&at.get())
+                                on:change:target=move |ev| {
+                                    if let Some(datetime) = input_to_datetime(&ev.target().value()) {
+                                        at.set(datetime);
+                                    }
+                                }
+                            />
+                        </label>
+                        <label class="label">
+                            <input
+                                type="checkbox"
+                                class="checkbox"
+                                prop:checked=include_unpublished
+                                on:change:target=move |ev| {
+                                    include_unpublished.set(ev.target().checked());
+                                }
+                            />
+                            "Include my unpublished slide groups"
+                        </label>
+                    </div>
+                    <p class="text-sm/6 text-current/60">
+                        "Dates are in Swedish time. Emergency messages are not included."
+                    </p>
+                </div>
+            </div>
+            <Transition fallback=|| view! { <div>Loading...</div> }.into_any()>
+                <ErrorBoundary fallback=|errors| {
+                    view! { <ErrorList errors=errors /> }
+                }>
+                    {move || Suspend::new(async move {
+                        let previews = previews_resource.await?;
+                        Ok::<
+                            _,
+                            api::AppError,
+                        >(
+                            view! {
+                                <p class="text-lg">
+                                    {format!("Shown at {}", fmt_datetime(&at.get_untracked()))}
+                                </p>
+                                <div class="grid grid-flow-col auto-cols-fr gap-4">
+                                    {previews
+                                        .into_iter()
+                                        .map(|preview| view! { <ScreenPreview preview /> })
+                                        .collect_view()}
+                                </div>
+                            },
+                        )
+                    })}
+                </ErrorBoundary>
+            </Transition>
+        </div>
+    }
+    .into_any()
+}
+
+/// The rotation of a single screen, in the order that its entries are shown.
+#[component]
+fn ScreenPreview(preview: FeedPreviewDto) -> impl IntoView {
+    let is_empty = preview.feed.entries.is_empty();
+
+    view! {
+        <div class="space-y-4">
+            <p class="uppercase text-current/80 font-bold text-sm">{preview.screen.name}</p>
+            <Show when=move || is_empty>
+                <div class="aspect-16/9 border bg-base-200 flex justify-center items-center">
+                    "Nothing is shown"
+                </div>
+            </Show>
+            {preview
+                .feed
+                .entries
+                .into_iter()
+                .map(|entry| view! { <PreviewEntry entry /> })
+                .collect_view()}
+        </div>
+    }
+}
+
+#[component]
+fn PreviewEntry(entry: FeedEntryDto) -> impl IntoView {
+    let content = match entry.content_type {
+        ContentType::Image => {
+            view! { <img class="object-contain h-full w-full" src=entry.url /> }.into_any()
+        }
+        ContentType::Video => view! {
+            <video
+                controls
+                muted
+                preload="metadata"
+                class="object-contain h-full w-full"
+                src=entry.url
+            />
+        }
+        .into_any(),
+        ContentType::Text => {
+            view! { <TextSlide text=entry.text.unwrap_or_default() background=entry.url /> }
+                .into_any()
+        }
+        ContentType::Html | ContentType::Url => view! {
+            <iframe
+                sandbox="allow-scripts allow-same-origin"
+                class="object-contain h-full w-full pointer-events-none"
+                src=entry.url
+            />
+        }
+        .into_any(),
+    };
+
+    view! {
+        <div>
+            <div class="aspect-16/9 border">{content}</div>
+            <p class="text-sm text-current/60">
+                {format!(
+                    "{} s, {}",
+                    entry.duration as f64 / 1000.0,
+                    fmt_transition(Some(entry.transition)),
+                )}
+            </p>
+        </div>
+    }
+}
@@ -1,15 +1,47 @@
-use crate::{api, components::feed::ScreenFeedSlideshow};
+use crate::{
+    api,
+    components::{
+        emergency_message::EmergencyMessage,
+        feed::{ScreenFeedSlideshow, SlideJump},
+        feed_status::FeedStatus,
+    },
+    utils::{
+        clock::use_server_clock,
+        offline::{clear_cache, load_feed, register_service_worker, save_feed},
+    },
+};
 use codee::string::JsonSerdeCodec;
-use common::dtos::FeedEntryDto;
-use leptos::prelude::*;
-use leptos_router::{hooks::use_params, params::Params};
-use leptos_use::{use_event_source_with_options, ReconnectLimit, UseEventSourceOptions};
+use common::dtos::{
+    EmergencyMessageDto, FeedDto, ScreenCommandDto, ScreenFeedEventDto, ScreenFeedPayloadDto,
+    SCREEN_FEED_EVENT_VERSION,
+};
+use gloo_timers::callback::Timeout;
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::{
+    hooks::{use_params, use_query},
+    params::Params,
+};
+use leptos_use::{
+    core::ConnectionReadyState, use_event_source_with_options, ReconnectLimit,
+    UseEventSourceOptions,
+};
 
 #[derive(Params, PartialEq)]
 struct ScreenFeedParams {
     id: Option<i32>,
 }
 
+#[derive(Params, PartialEq)]
+struct ScreenFeedQuery {
+    // How many upcoming slides to preload.
+    lookahead: Option<usize>,
+}
+
+const DEFAULT_LOOKAHEAD: usize = 2;
+
+/// How long the name of the screen is shown when it's identified.
+const IDENTIFICATION_DURATION_MS: u32 = 10_000;
+
 /// Page to display the slideshow on a given TV
 #[component]
 pub fn ScreenFeed() -> impl IntoView {
@@ -20,24 +52,152 @@ pub fn ScreenFeed() -> impl IntoView {
         .ok()
         .and_then(|params| params.id)
         .unwrap_or_default();
+    let lookahead = use_query::<ScreenFeedQuery>()
+        .read_untracked()
+        .as_ref()
+        .ok()
+        .and_then(|query| query.lookahead)
+        .unwrap_or(DEFAULT_LOOKAHEAD);
 
-    let event_source = use_event_source_with_options::<Vec<FeedEntryDto>, JsonSerdeCodec>(
+    let event_source = use_event_source_with_options::<ScreenFeedEventDto, JsonSerdeCodec>(
         &api::get_screen_feed_url(id),
         UseEventSourceOptions::default()
             .reconnect_limit(ReconnectLimit::Infinite)
-            .reconnect_interval(10_000), // 10 seconds
+            .reconnect_interval(10_000) // 10 seconds
+            .named_events(
+                ["feed", "feed-error", "alert", "command"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>(),
+            ),
     );
 
-    let data = move || event_source.data.get().unwrap_or_default();
+    let clock = use_server_clock();
+    let identification = RwSignal::new(None::<String>);
+    let identification_handle_signal = RwSignal::new_local(None::<Timeout>);
+    let jump = RwSignal::new(None::<SlideJump>);
+
+    // All events arrive on the same signal, so keep the latest of each
+    let received_feed = RwSignal::new(None::<FeedDto>);
+    let feed_error = RwSignal::new(None::<String>);
+    let alert = RwSignal::new(None::<EmergencyMessageDto>);
+    let handle_payload = move |payload: ScreenFeedPayloadDto| match payload {
+        ScreenFeedPayloadDto::Feed(feed) => {
+            feed_error.set(None);
+            received_feed.set(Some(feed));
+        }
+        // the last feed is kept, which is better than showing nothing
+        ScreenFeedPayloadDto::FeedError(err) => feed_error.set(Some(err.msg)),
+        ScreenFeedPayloadDto::Alert(message) => alert.set(message),
+        ScreenFeedPayloadDto::Command(command) => match command {
+            ScreenCommandDto::Reload => reload(),
+            ScreenCommandDto::Identify => spawn_local(async move {
+                let name = api::list_screens()
+                    .await
+                    .ok()
+                    .and_then(|screens| screens.into_iter().find(|screen| screen.id == id))
+                    .map(|screen| screen.name);
+                identification.set(Some(match name {
+                    Some(name) => format!("{name} (screen {id})"),
+                    None => format!("Screen {id}"),
+                }));
+                let handle = Timeout::new(IDENTIFICATION_DURATION_MS, move || {
+                    identification.set(None);
+                });
+                identification_handle_signal.update(|handle_slot| {
+                    if let Some(previous) = handle_slot.replace(handle) {
+                        previous.cancel();
+                    }
+                });
+            }),
+            ScreenCommandDto::ClearCache => spawn_local(async move {
+                clear_cache(id).await;
+                reload();
+            }),
+            ScreenCommandDto::JumpToSlide { index } => jump.set(Some(SlideJump {
+                index,
+                started_at: clock.now(),
+            })),
+        },
+    };
+    Effect::new(move || {
+        let Some(event) = event_source.data.get() else {
+            return;
+        };
+        if event.version != SCREEN_FEED_EVENT_VERSION {
+            feed_error.set(Some(format!(
+                "Unsupported feed version {}, reload the page",
+                event.version
+            )));
+            return;
+        }
+        handle_payload(event.payload);
+    });
+    on_cleanup(move || {
+        identification_handle_signal.update(|handle| {
+            if let Some(handle) = handle.take() {
+                handle.cancel();
+            }
+        });
+    });
+
+    // Fall back to the last received feed if the page is loaded while offline
+    let cached_feed = load_feed(id);
+    let feed = Memo::new(move |_| received_feed.get().or_else(|| cached_feed.clone()));
+
+    register_service_worker(move || {
+        if let Some(Some(feed)) = feed.try_get_untracked() {
+            save_feed(id, &feed);
+        }
+    });
+
+    Effect::new(move || {
+        if let Some(feed) = received_feed.get() {
+            clock.observe(feed.server_time);
+            save_feed(id, &feed);
+        }
+    });
+
+    // The same feed is received again after reconnecting, which shouldn't restart the slideshow
+    let version = Memo::new(move |_| feed.get().map(|feed| feed.version));
+    let data = move || {
+        version.track();
+        feed.get_untracked()
+            .map(|feed| feed.entries)
+            .unwrap_or_default()
+    };
+
+    let problem = Signal::derive(move || match event_source.ready_state.get() {
+        ConnectionReadyState::Open => feed_error.get(),
+        ConnectionReadyState::Connecting => Some("Connecting to the server".to_string()),
+        _ => Some("Not connected to the server".to_string()),
+    });
 
     view! {
         <Transition fallback=|| {
             view! { <div>Loading...</div> }
         }>
         <div class="bg-black">
-            <ScreenFeedSlideshow feed=Signal::derive(data) />
+            <ScreenFeedSlideshow feed=Signal::derive(data) clock lookahead jump />
+            {move || {
+                identification
+                    .get()
+                    .map(|name| {
+                        view! {
+                            <div class="fixed inset-x-0 top-0 z-40 bg-black/75 p-8 text-center text-6xl font-bold text-white">
+                                {name}
+                            </div>
+                        }
+                    })
+            }}
+            <FeedStatus problem />
+            {move || alert.get().map(|message| view! { <EmergencyMessage message /> })}
         </div>
         </Transition>
     }
     .into_any()
 }
+
+fn reload() {
+    let _ = window().location().reload();
+}
@@ -0,0 +1,372 @@
+use crate::{
+    api,
+    components::{content::ContentItem, error::ErrorList, transition_input::TransitionInput},
+    utils::datetime::{fmt_datetime, fmt_datetime_opt},
+};
+use common::dtos::{
+    ContentDto, EditScreenDto, ScreenCommandDto, ScreenConnectionDto, ScreenDto, ScreenLayoutDto,
+    ScreenStatusDto,
+};
+use gloo_timers::callback::Interval;
+use leptos::prelude::*;
+use reactive_stores::Store;
+
+/// How often the status of the screens is refreshed.
+const REFRESH_INTERVAL_MS: u32 = 10_000;
+
+/// Admin page showing which screens are connected to their feed, with remote controls and the
+/// fallback content for them.
+#[component]
+pub fn Screens() -> impl IntoView {
+    let screens_resource = LocalResource::new(move || async move { api::list_screens().await });
+    let statuses_resource =
+        LocalResource::new(move || async move { api::list_screen_statuses().await });
+
+    let interval_handle =
+        RwSignal::new_local(Some(Interval::new(REFRESH_INTERVAL_MS, move || {
+            statuses_resource.refetch();
+        })));
+    on_cleanup(move || {
+        interval_handle.update(|handle| {
+            if let Some(handle) = handle.take() {
+                handle.cancel();
+            }
+        });
+    });
+
+    view! {
+        <Transition fallback=|| view! { <div>Loading...</div> }.into_any()>
+            <ErrorBoundary fallback=|errors| {
+                view! { <ErrorList errors=errors /> }
+            }>
+                <div class="container m-auto my-4 space-y-8">
+                    <div class="card">
+                        <div class="card-body">
+                            <h2 class="card-title text-2xl">"All screens"</h2>
+                            <ScreenCommands screen=None />
+                        </div>
+                    </div>
+                    {move || statuses_resource.get().map(|result| result.map(|_| ()))}
+                    {move || Suspend::new(async move {
+                        let screens = screens_resource.await?;
+                        Ok::<_, api::AppError>(
+                            screens
+                                .into_iter()
+                                .map(|screen| {
+                                    // statuses are refreshed without rerendering the screens
+                                    let status = Signal::derive(move || {
+                                        statuses_resource
+                                            .get()
+                                            .and_then(|statuses| statuses.ok())
+                                            .and_then(|statuses| {
+                                                statuses
+                                                    .into_iter()
+                                                    .find(|status| status.screen == screen.id)
+                                            })
+                                    });
+                                    view! { <ScreenStatus screen status /> }
+                                })
+                                .collect_view(),
+                        )
+                    })}
+                </div>
+            </ErrorBoundary>
+        </Transition>
+    }
+    .into_any()
+}
+
+#[component]
+fn ScreenStatus(screen: ScreenDto, status: Signal<Option<ScreenStatusDto>>) -> impl IntoView {
+    let id = screen.id;
+    let connections = move || {
+        status
+            .get()
+            .map(|status| status.connections)
+            .unwrap_or_default()
+    };
+    let is_online = move || !connections().is_empty();
+
+    view! {
+        <div class="card">
+            <div class="card-body">
+                <h2 class="card-title text-2xl">
+                    {screen.name.clone()}
+                    <Show
+                        when=is_online
+                        fallback=|| view! { <span class="badge badge-error">"Offline"</span> }
+                    >
+                        <span class="badge badge-success">"Online"</span>
+                    </Show>
+                </h2>
+                <Show
+                    when=is_online
+                    fallback=move || {
+                        view! {
+                            <p>
+                                {move || {
+                                    status
+                                        .get()
+                                        .and_then(|status| status.disconnected_since)
+                                        .map(|since| {
+                                            format!("Disconnected since {}", fmt_datetime(&since))
+                                        })
+                                        .unwrap_or_else(|| {
+                                            "Not connected since the server started".to_string()
+                                        })
+                                }}
+                            </p>
+                        }
+                    }
+                >
+                    <ul class="list">
+                        {move || {
+                            connections()
+                                .into_iter()
+                                .map(|connection| view! { <ScreenConnection connection /> })
+                                .collect_view()
+                        }}
+                    </ul>
+                </Show>
+                <ScreenCommands screen=Some(id) />
+                <ScreenSettings screen=screen.clone() />
+                <FallbackContent screen />
+            </div>
+        </div>
+    }
+}
+
+/// Buttons for sending commands to the clients of a screen, or of every screen if `None`.
+#[component]
+fn ScreenCommands(screen: Option<i32>) -> impl IntoView {
+    let slide_number = RwSignal::new(1_usize);
+    let command_action = Action::new_local(move |command: &ScreenCommandDto| {
+        let command = command.clone();
+        async move { api::send_screen_command(screen, &command).await }
+    });
+    let disabled = move || command_action.pending().get();
+
+    view! {
+        <ErrorBoundary fallback=|errors| {
+            view! { <ErrorList errors=errors /> }.into_any()
+        }>{move || command_action.value().get()}</ErrorBoundary>
+        <div class="flex flex-wrap items-center gap-2">
+            <button
+                class="btn"
+                on:click=move |_| {
+                    command_action.dispatch(ScreenCommandDto::Reload);
+                }
+                disabled=disabled
+            >
+                "Reload"
+            </button>
+            <button
+                class="btn"
+                on:click=move |_| {
+                    command_action.dispatch(ScreenCommandDto::Identify);
+                }
+                disabled=disabled
+            >
+                "Identify"
+            </button>
+            <button
+                class="btn"
+                on:click=move |_| {
+                    command_action.dispatch(ScreenCommandDto::ClearCache);
+                }
+                disabled=disabled
+            >
+                "Clear cache"
+            </button>
+            <div class="join">
+                <label class="input join-item w-fit">
+                    <span class="label">"Slide"</span>
+                    <input
+                        class="w-16"
+                        type="number"
+                        min=1
+                        prop:value=move || slide_number.get()
+                        on:change:target=move |ev| {
+                            if let Ok(value) = ev.target().value().parse::<usize>() {
+                                slide_number.set(value.max(1));
+                            }
+                        }
+                    />
+                </label>
+                <button
+                    class="btn join-item"
+                    on:click=move |_| {
+                        command_action
+                            .dispatch(ScreenCommandDto::JumpToSlide {
+                                index: slide_number.get_untracked() - 1,
+                            });
+                    }
+                    disabled=disabled
+                >
+                    "Show slide"
+                </button>
+            </div>
+        </div>
+    }
+}
+
+/// Defaults of the screen, used for the slide groups which don't set their own, and its layout on
+/// the wall, used to split content spanning all screens.
+#[component]
+fn ScreenSettings(screen: ScreenDto) -> impl IntoView {
+    let transition = Store::new(Some(screen.transition));
+    let width = RwSignal::new(screen.layout.width);
+    let height = RwSignal::new(screen.layout.height);
+    let bezel_gap = RwSignal::new(screen.layout.bezel_gap);
+
+    let save_action = Action::new_local(move |_: &()| {
+        let edit = EditScreenDto {
+            name: screen.name.clone(),
+            position: screen.position,
+            transition: transition.get_untracked().unwrap_or_default(),
+            layout: ScreenLayoutDto {
+                width: width.get_untracked(),
+                height: height.get_untracked(),
+                bezel_gap: bezel_gap.get_untracked(),
+            },
+        };
+        async move { api::update_screen(screen.id, &edit).await }
+    });
+    let disabled = Signal::derive(move || save_action.pending().get());
+
+    view! {
+        <div class="space-y-2">
+            <h3 class="text-lg font-bold">"Settings"</h3>
+            <ErrorBoundary fallback=|errors| {
+                view! { <ErrorList errors=errors /> }.into_any()
+            }>{move || save_action.value().get()}</ErrorBoundary>
+            <div class="flex flex-wrap items-center gap-2">
+                <TransitionInput transition disabled required=true />
+            </div>
+            <div class="flex flex-wrap items-center gap-2">
+                <PixelInput label="Width" value=width min=1 disabled />
+                <PixelInput label="Height" value=height min=1 disabled />
+                <PixelInput label="Bezel gap" value=bezel_gap min=0 disabled />
+            </div>
+            <button
+                class="btn"
+                on:click=move |_| {
+                    save_action.dispatch(());
+                }
+                disabled=disabled
+            >
+                "Save"
+            </button>
+        </div>
+    }
+}
+
+/// Input for a length in pixels of the screen, which can't be less than `min`.
+#[component]
+fn PixelInput(
+    label: &'static str,
+    value: RwSignal<i32>,
+    min: i32,
+    disabled: Signal<bool>,
+) -> impl IntoView {
+    view! {
+        <label class="input w-fit">
+            <span class="label">{label}</span>
+            <input
+                class="w-20"
+                type="number"
+                min=min
+                prop:value=move || value.get()
+                on:change:target=move |ev| {
+                    if let Ok(pixels) = ev.target().value().parse::<i32>() {
+                        value.set(pixels.max(min));
+                    }
+                }
+                disabled=disabled
+            />
+            <span class="label">"px"</span>
+        </label>
+    }
+}
+
+/// Content shown on the screen when no slide group is to be shown.
+#[component]
+fn FallbackContent(screen: ScreenDto) -> impl IntoView {
+    let id = screen.id;
+    let content = RwSignal::new(None::<ContentDto>);
+    let content_resource = LocalResource::new(move || async move {
+        let fallback = api::get_fallback_content(id).await?;
+        content.set(fallback);
+        Ok::<_, api::AppError>(())
+    });
+
+    let set_action = Action::new_local(move |fallback: &Option<ContentDto>| {
+        let fallback = fallback.clone();
+        async move {
+            api::set_fallback_content(id, fallback.as_ref().map(|content| content.id)).await?;
+            content.set(fallback);
+            Ok::<_, api::AppError>(())
+        }
+    });
+    let disabled = move || set_action.pending().get();
+
+    view! {
+        <div class="space-y-2">
+            <h3 class="text-lg font-bold">"Fallback content"</h3>
+            <p class="text-sm opacity-60">
+                "Shown on the screen when there are no slides to show."
+            </p>
+            <ErrorBoundary fallback=|errors| {
+                view! { <ErrorList errors=errors /> }.into_any()
+            }>
+                {move || content_resource.get()} {move || set_action.value().get()}
+            </ErrorBoundary>
+            <div class="max-w-md">
+                <ContentItem
+                    screen
+                    content=content
+                    on_submit=move |content| {
+                        set_action.dispatch(Some(content));
+                    }
+                    editable=Signal::derive(move || !disabled())
+                />
+            </div>
+            <Show when=move || content.with(|content| content.is_some())>
+                <button
+                    class="btn"
+                    on:click=move |_| {
+                        set_action.dispatch(None);
+                    }
+                    disabled=disabled
+                >
+                    "Remove fallback content"
+                </button>
+            </Show>
+        </div>
+    }
+}
+
+#[component]
+fn ScreenConnection(connection: ScreenConnectionDto) -> impl IntoView {
+    view! {
+        <li class="list-row">
+            <div>
+                <p class="font-bold">
+                    {connection.remote_address.unwrap_or_else(|| "Unknown address".to_string())}
+                </p>
+                <p class="text-sm opacity-60">
+                    {connection.user_agent.unwrap_or_else(|| "Unknown user agent".to_string())}
+                </p>
+            </div>
+            <div class="text-right">
+                <p>{format!("Connected since {}", fmt_datetime(&connection.connected_since))}</p>
+                <p class="text-sm opacity-60">
+                    {format!(
+                        "Last event: {}",
+                        fmt_datetime_opt(connection.last_event.as_ref(), "None"),
+                    )}
+                </p>
+            </div>
+        </li>
+    }
+}
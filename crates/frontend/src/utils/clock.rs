@@ -0,0 +1,102 @@
+//! Estimates the offset between the local clock and the server's, so that screens whose clocks
+//! are set incorrectly still show the same slide at the same time as their neighbours.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use gloo_timers::callback::Interval;
+use leptos::{logging, prelude::*, task::spawn_local};
+
+use crate::api;
+
+/// How often the offset is measured again, to correct for the local clock drifting.
+const SYNC_INTERVAL_MS: u32 = 60_000;
+
+/// Number of round trips per measurement. The one with the shortest round trip is used, since it
+/// has the smallest uncertainty.
+const SAMPLES_PER_SYNC: usize = 4;
+
+/// Changes to the offset smaller than this are ignored, so that the slideshow isn't rescheduled
+/// because of network jitter.
+const OFFSET_TOLERANCE_MS: i64 = 20;
+
+#[derive(Clone, Copy)]
+pub struct ServerClock {
+    offset_ms: RwSignal<i64>,
+    measured: StoredValue<bool>,
+}
+
+impl ServerClock {
+    /// The current time according to the server.
+    pub fn now(&self) -> DateTime<Utc> {
+        Utc::now() + TimeDelta::milliseconds(self.offset_ms.try_get_untracked().unwrap_or(0))
+    }
+
+    /// Subscribe to corrections of the offset.
+    pub fn track(&self) {
+        self.offset_ms.track();
+    }
+
+    /// Use a server time which was received without measuring the round trip, which is only
+    /// done until the offset has been properly measured.
+    pub fn observe(&self, server_time: DateTime<Utc>) {
+        if !self.measured.try_get_value().unwrap_or(true) {
+            self.set_offset((server_time - Utc::now()).num_milliseconds());
+        }
+    }
+
+    fn set_offset(&self, offset_ms: i64) {
+        let Some(current) = self.offset_ms.try_get_untracked() else {
+            return;
+        };
+        if (offset_ms - current).abs() >= OFFSET_TOLERANCE_MS {
+            self.offset_ms.try_set(offset_ms);
+        }
+    }
+}
+
+/// Create a clock which is periodically synchronized with the server.
+pub fn use_server_clock() -> ServerClock {
+    let clock = ServerClock {
+        offset_ms: RwSignal::new(0),
+        measured: StoredValue::new(false),
+    };
+
+    spawn_local(sync(clock));
+    let interval_handle = RwSignal::new_local(Some(Interval::new(SYNC_INTERVAL_MS, move || {
+        spawn_local(sync(clock));
+    })));
+    on_cleanup(move || {
+        interval_handle.update(|handle| {
+            if let Some(handle) = handle.take() {
+                handle.cancel();
+            }
+        });
+    });
+
+    clock
+}
+
+/// Measure the offset NTP-style, assuming the server read its clock halfway through the round trip.
+async fn sync(clock: ServerClock) {
+    let mut best: Option<(TimeDelta, i64)> = None;
+    for _ in 0..SAMPLES_PER_SYNC {
+        let sent = Utc::now();
+        let server_time = match api::get_server_time().await {
+            Ok(server_time) => server_time.time,
+            Err(err) => {
+                logging::log!("Failed to get server time: {err}");
+                continue;
+            }
+        };
+        let round_trip = Utc::now() - sent;
+
+        let offset_ms = (server_time - (sent + round_trip / 2)).num_milliseconds();
+        if best.is_none_or(|(shortest, _)| round_trip < shortest) {
+            best = Some((round_trip, offset_ms));
+        }
+    }
+
+    if let Some((_, offset_ms)) = best {
+        clock.measured.try_set_value(true);
+        clock.set_offset(offset_ms);
+    }
+}
@@ -1,8 +1,11 @@
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, NaiveTime, Utc, Weekday};
 use chrono_tz::{Europe, Tz};
+use common::dtos::RecurrenceDto;
 
 const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M";
 
+const TIME_FORMAT: &str = "%H:%M";
+
 const TIME_ZONE: Tz = Europe::Stockholm;
 
 pub fn datetime_to_input(datetime: &DateTime<Utc>) -> String {
@@ -33,3 +36,65 @@ pub fn fmt_datetime_opt(datetime: Option<&DateTime<Utc>>, none: &'static str) ->
         None => none.to_string(),
     }
 }
+
+/// Format a length of media or of a slide in seconds, e.g. `4.5 s`, or as `m:ss` once it is at
+/// least a minute long.
+pub fn fmt_duration_ms(duration_ms: i32) -> String {
+    if duration_ms < 60_000 {
+        return format!("{} s", (duration_ms as f64 / 100.0).round() / 10.0);
+    }
+    let secs = (duration_ms + 500) / 1000;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+pub fn time_to_input(time: &NaiveTime) -> String {
+    time.format(TIME_FORMAT).to_string()
+}
+
+pub fn input_to_time(input: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(input, TIME_FORMAT).ok()
+}
+
+pub fn fmt_recurrence(recurrence: &RecurrenceDto) -> String {
+    let weekdays = if recurrence.weekdays.len() == 7 {
+        "Every day".to_string()
+    } else {
+        recurrence
+            .weekdays
+            .iter()
+            .map(|day| fmt_weekday(*day))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    format!(
+        "{weekdays} {}\u{2013}{}",
+        time_to_input(&recurrence.start_time),
+        time_to_input(&recurrence.end_time)
+    )
+}
+
+pub fn fmt_weekday(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Mon",
+        Weekday::Tue => "Tue",
+        Weekday::Wed => "Wed",
+        Weekday::Thu => "Thu",
+        Weekday::Fri => "Fri",
+        Weekday::Sat => "Sat",
+        Weekday::Sun => "Sun",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fmt_duration_ms;
+
+    #[test]
+    fn durations() {
+        assert_eq!(fmt_duration_ms(4_000), "4 s");
+        assert_eq!(fmt_duration_ms(12_345), "12.3 s");
+        assert_eq!(fmt_duration_ms(60_000), "1:00");
+        assert_eq!(fmt_duration_ms(754_600), "12:35");
+    }
+}
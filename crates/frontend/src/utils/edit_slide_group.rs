@@ -3,7 +3,10 @@
 //! `EditSlideGroupDto` since the relevant APIs isn't supposed to edit the content data.
 
 use chrono::{DateTime, Utc};
-use common::dtos::{ContentDto, EditSlideDto, EditSlideGroupDto, OwnerDto, SlideGroupDto};
+use common::dtos::{
+    ContentDto, EditSlideDto, EditSlideGroupDto, OwnerDto, RecurrenceDto, SlideGroupDto,
+    TransitionDto,
+};
 use reactive_stores::Store;
 
 #[derive(Clone, Debug, PartialEq, Eq, Default, Store)]
@@ -11,10 +14,14 @@ pub struct EditSlideGroup {
     pub id: i32,
     pub title: String,
     pub priority: i32,
+    pub takeover: bool,
     pub hidden: bool,
     pub created_by: OwnerDto,
     pub start_date: DateTime<Utc>,
     pub end_date: Option<DateTime<Utc>>,
+    pub recurrence: Option<RecurrenceDto>,
+    pub transition: Option<TransitionDto>,
+    pub screens: Vec<i32>,
     pub archive_date: Option<DateTime<Utc>>,
     pub published: bool,
     #[store(key: (Option<i32>, i32) = |slide| slide.id())]
@@ -27,10 +34,14 @@ impl From<SlideGroupDto> for EditSlideGroup {
             id: value.id,
             title: value.title,
             priority: value.priority,
+            takeover: value.takeover,
             hidden: value.hidden,
             created_by: value.created_by,
             start_date: value.start_date,
             end_date: value.end_date,
+            recurrence: value.recurrence,
+            transition: value.transition,
+            screens: value.screens,
             archive_date: value.archive_date,
             published: value.published,
             slides: value
@@ -42,6 +53,7 @@ impl From<SlideGroupDto> for EditSlideGroup {
                         archive_date: slide.archive_date,
                     }),
                     position: slide.position,
+                    duration: slide.duration,
                     content: slide.content.into_iter().collect(),
                 })
                 .collect(),
@@ -55,10 +67,14 @@ impl From<EditSlideGroup> for EditSlideGroupDto {
             id: value.id,
             title: value.title,
             priority: value.priority,
+            takeover: value.takeover,
             hidden: value.hidden,
             created_by: value.created_by,
             start_date: value.start_date,
             end_date: value.end_date,
+            recurrence: value.recurrence,
+            transition: value.transition,
+            screens: value.screens,
             archive_date: value.archive_date,
             published: value.published,
             slides: value
@@ -69,6 +85,7 @@ impl From<EditSlideGroup> for EditSlideGroupDto {
                         id,
                         position: slide.position,
                         archive_date,
+                        duration: slide.duration,
                         content: slide
                             .content
                             .into_iter()
@@ -77,6 +94,7 @@ impl From<EditSlideGroup> for EditSlideGroupDto {
                     },
                     None => EditSlideDto::New {
                         position: slide.position,
+                        duration: slide.duration,
                         content: slide
                             .content
                             .into_iter()
@@ -94,6 +112,8 @@ pub struct EditSlide {
     // Is none if the slide hasn't been created server side yet.
     pub existing: Option<ExistingSlide>,
     pub position: i32,
+    // Display duration in milliseconds. None signifies the default duration.
+    pub duration: Option<i32>,
     // List of content entity IDs.
     pub content: Vec<ContentDto>,
 }
@@ -119,6 +139,7 @@ impl Default for EditSlide {
         Self {
             existing: None,
             position: 0,
+            duration: None,
             content: Vec::new(),
         }
     }
@@ -0,0 +1,116 @@
+use common::dtos::ALLOWED_URL_SCHEMES;
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+
+/// Render the markdown of a text slide to HTML. Raw HTML is escaped and links and images with
+/// other schemes than [`ALLOWED_URL_SCHEMES`] are dropped, so that slides can't run scripts on the
+/// screens.
+pub fn render_markdown(markdown: &str) -> String {
+    let parser =
+        Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH).map(|event| match event {
+            Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Link {
+                link_type,
+                dest_url: safe_url(dest_url),
+                title,
+                id,
+            }),
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Image {
+                link_type,
+                dest_url: safe_url(dest_url),
+                title,
+                id,
+            }),
+            event => event,
+        });
+
+    let mut output = String::new();
+    html::push_html(&mut output, parser);
+    output
+}
+
+/// Keep the url of a link or image if it is relative or uses one of [`ALLOWED_URL_SCHEMES`], and
+/// replace it with an empty one otherwise.
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    // browsers ignore whitespace and control characters in urls, e.g. in `java\tscript:`
+    let normalized: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect();
+    let is_allowed = match normalized.split_once(':') {
+        // a colon after the path has started isn't part of a scheme
+        Some((scheme, _)) if !scheme.contains(['/', '?', '#']) => ALLOWED_URL_SCHEMES
+            .iter()
+            .any(|allowed| scheme.eq_ignore_ascii_case(allowed)),
+        _ => true,
+    };
+    if is_allowed {
+        url
+    } else {
+        CowStr::Borrowed("")
+    }
+}
+
+/// Roughly how many characters fit on a line of a text slide at the largest font size.
+const CHARS_PER_LINE: usize = 24;
+
+/// Font size of a text slide relative to its height, in percent, so that longer text is made
+/// smaller to fit. Only depends on the markdown, so that the slide looks the same at any size.
+pub fn text_font_size(markdown: &str) -> f64 {
+    let lines: usize = markdown
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            // headings are twice as large, see `.text-slide` in the styles
+            let width = if line.starts_with('#') { 2 } else { 1 };
+            line.chars().count().div_ceil(CHARS_PER_LINE / width).max(1) * width
+        })
+        .sum();
+
+    // leave room for the spacing between paragraphs
+    (60.0 / lines.max(1) as f64).clamp(2.5, 10.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_markdown;
+
+    #[test]
+    fn unsafe_urls_are_dropped() {
+        for markdown in [
+            "[click](javascript:alert(1))",
+            "[click](JavaScript:alert(1))",
+            "[click](<java\tscript:alert(1)>)",
+            "<javascript:alert(1)>",
+            "[click](data:text/html;base64,PHNjcmlwdD4=)",
+        ] {
+            let html = render_markdown(markdown);
+            assert!(html.contains("<a href=\"\">"), "{html}");
+        }
+
+        let html = render_markdown("![image](data:image/svg+xml;base64,PHN2Zz4=)");
+        assert!(html.contains("<img src=\"\""), "{html}");
+    }
+
+    #[test]
+    fn safe_urls_are_kept() {
+        assert_eq!(
+            render_markdown("[home](https://example.com/a:b)"),
+            "<p><a href=\"https://example.com/a:b\">home</a></p>\n"
+        );
+        assert_eq!(
+            render_markdown("![logo](images/logo.png)"),
+            "<p><img src=\"images/logo.png\" alt=\"logo\" /></p>\n"
+        );
+    }
+}
@@ -1,4 +1,7 @@
 pub mod bool;
+pub mod clock;
 pub mod datetime;
 pub mod dom_id;
 pub mod edit_slide_group;
+pub mod markdown;
+pub mod offline;
@@ -0,0 +1,199 @@
+//! Lets screens keep showing their slideshow while the backend or S3 is unreachable. The last
+//! received feed is stored in local storage, and the media it references is cached by the service
+//! worker in `public/sw.js`.
+
+use common::dtos::{ContentType, FeedDto, FeedEntryDto};
+use leptos::logging;
+use serde_json::json;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    js_sys::{Array, Reflect},
+    wasm_bindgen::{closure::Closure, JsCast, JsValue},
+    RegistrationOptions,
+};
+
+const SERVICE_WORKER_URL: &str = "/sw.js";
+
+/// Only the screen feed pages are controlled by the service worker.
+const SERVICE_WORKER_SCOPE: &str = "/feed/";
+
+/// Register the service worker, if the browser supports it (which requires a secure context).
+///
+/// `on_controller_change` is called once the service worker starts controlling the page, which
+/// doesn't happen until after the first feed has been received when the page is first loaded.
+pub fn register_service_worker(on_controller_change: impl Fn() + 'static) {
+    let Some(navigator) = web_sys::window().map(|window| window.navigator()) else {
+        return;
+    };
+    if !Reflect::has(&navigator, &JsValue::from_str("serviceWorker")).unwrap_or(false) {
+        logging::log!("Service workers are unavailable, the feed won't be cached");
+        return;
+    }
+
+    let container = navigator.service_worker();
+    let on_controller_change = Closure::<dyn Fn()>::new(on_controller_change);
+    container.set_oncontrollerchange(Some(on_controller_change.as_ref().unchecked_ref()));
+    // the page lives as long as the screen is on, so the listener is never removed
+    on_controller_change.forget();
+
+    let options = RegistrationOptions::new();
+    options.set_scope(SERVICE_WORKER_SCOPE);
+    // the promise is only rejected if registration fails, in which case caching is skipped
+    let _ = container.register_with_options(SERVICE_WORKER_URL, &options);
+}
+
+/// Store the feed so that it can be shown if the page is loaded while offline, and have the
+/// service worker cache its media.
+pub fn save_feed(screen: i32, feed: &FeedDto) {
+    if let (Some(storage), Ok(value)) = (local_storage(), serde_json::to_string(feed)) {
+        let _ = storage.set_item(&feed_key(screen), &value);
+    }
+
+    let Some(navigator) = web_sys::window().map(|window| window.navigator()) else {
+        return;
+    };
+    if !Reflect::has(&navigator, &JsValue::from_str("serviceWorker")).unwrap_or(false) {
+        return;
+    }
+    // the page isn't controlled by the service worker until it has been activated
+    if let Some(service_worker) = navigator.service_worker().controller() {
+        let message =
+            json!({ "type": "cache-media", "urls": media_urls(&feed.entries) }).to_string();
+        let _ = service_worker.post_message(&JsValue::from_str(&message));
+    }
+}
+
+/// Urls of the media which the service worker should cache for the given entries, including the
+/// backgrounds of text. Web pages are loaded by the browser on their own.
+fn media_urls(entries: &[FeedEntryDto]) -> Vec<&str> {
+    entries
+        .iter()
+        .filter(|entry| entry.content_type != ContentType::Url)
+        .map(|entry| entry.url.as_str())
+        // slides without content on this screen have an empty url
+        .filter(|url| !url.is_empty())
+        .collect()
+}
+
+/// Get the last feed stored by [`save_feed`].
+pub fn load_feed(screen: i32) -> Option<FeedDto> {
+    let value = local_storage()?.get_item(&feed_key(screen)).ok()??;
+    serde_json::from_str(&value).ok()
+}
+
+/// Remove the stored feed and everything cached by the service worker, so that it's all fetched
+/// again.
+pub async fn clear_cache(screen: i32) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(&feed_key(screen));
+    }
+
+    // caches are only available in a secure context
+    let Some(caches) = web_sys::window().and_then(|window| window.caches().ok()) else {
+        return;
+    };
+    let Ok(names) = JsFuture::from(caches.keys()).await else {
+        return;
+    };
+    for name in Array::from(&names).iter() {
+        if let Some(name) = name.as_string() {
+            let _ = JsFuture::from(caches.delete(&name)).await;
+        }
+    }
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+fn feed_key(screen: i32) -> String {
+    format!("feed-{screen}")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use common::dtos::{ContentType, FeedDto, FeedEntryDto, TransitionDto};
+
+    use super::media_urls;
+
+    fn entry(url: &str) -> FeedEntryDto {
+        FeedEntryDto {
+            content_type: ContentType::Image,
+            url: url.to_string(),
+            duration: 10_000,
+            end_date: None,
+            transition: TransitionDto::default(),
+            text: None,
+        }
+    }
+
+    #[test]
+    fn padding_is_not_cached() {
+        let entries = [entry("https://example.com/a.png"), entry("")];
+        assert_eq!(media_urls(&entries), vec!["https://example.com/a.png"]);
+    }
+
+    #[test]
+    fn pages_are_not_cached() {
+        let entries = [
+            FeedEntryDto {
+                content_type: ContentType::Url,
+                ..entry("https://example.com/")
+            },
+            FeedEntryDto {
+                content_type: ContentType::Text,
+                ..entry("https://example.com/background.png")
+            },
+            FeedEntryDto {
+                content_type: ContentType::Text,
+                ..entry("")
+            },
+            entry("https://example.com/a.png"),
+        ];
+        assert_eq!(
+            media_urls(&entries),
+            vec![
+                "https://example.com/background.png",
+                "https://example.com/a.png"
+            ]
+        );
+    }
+
+    #[test]
+    fn stored_feed_round_trip() {
+        let feed = FeedDto {
+            version: "abc".to_string(),
+            entries: vec![FeedEntryDto {
+                end_date: Some(Utc::now()),
+                ..entry("https://example.com/a.png")
+            }],
+            server_time: Utc::now(),
+        };
+        let stored = serde_json::to_string(&feed).unwrap();
+        assert_eq!(serde_json::from_str::<FeedDto>(&stored).unwrap(), feed);
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod browser_tests {
+    use chrono::Utc;
+    use common::dtos::FeedDto;
+    use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+    use super::{load_feed, save_feed};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn save_and_load_feed() {
+        let feed = FeedDto {
+            version: "abc".to_string(),
+            entries: vec![],
+            server_time: Utc::now(),
+        };
+        save_feed(1, &feed);
+        assert_eq!(load_feed(1), Some(feed));
+        assert_eq!(load_feed(2), None);
+    }
+}
@@ -3,6 +3,19 @@ pub use sea_orm_migration::prelude::*;
 mod m20250206_144027_create_slides_tables;
 mod m20250213_173223_add_published_slide_group;
 mod m20260317_000001_make_content_slide_nullable;
+mod m20261017_000001_add_slide_duration;
+mod m20261017_000002_add_content_media_metadata;
+mod m20261017_000003_add_slide_group_takeover;
+mod m20261017_000004_add_slide_group_recurrence;
+mod m20261017_000005_create_slide_group_screen;
+mod m20261017_000006_add_transitions;
+mod m20261017_000007_create_emergency_message;
+mod m20261017_000008_add_screen_fallback_content;
+mod m20261017_000009_add_screen_layout;
+mod m20261017_000010_add_url_content_type;
+mod m20261017_000011_add_text_content;
+mod m20261017_000012_add_content_renditions;
+mod m20261017_000013_add_content_video_codec;
 
 pub struct Migrator;
 
@@ -13,6 +26,19 @@ impl MigratorTrait for Migrator {
             Box::new(m20250206_144027_create_slides_tables::Migration),
             Box::new(m20250213_173223_add_published_slide_group::Migration),
             Box::new(m20260317_000001_make_content_slide_nullable::Migration),
+            Box::new(m20261017_000001_add_slide_duration::Migration),
+            Box::new(m20261017_000002_add_content_media_metadata::Migration),
+            Box::new(m20261017_000003_add_slide_group_takeover::Migration),
+            Box::new(m20261017_000004_add_slide_group_recurrence::Migration),
+            Box::new(m20261017_000005_create_slide_group_screen::Migration),
+            Box::new(m20261017_000006_add_transitions::Migration),
+            Box::new(m20261017_000007_create_emergency_message::Migration),
+            Box::new(m20261017_000008_add_screen_fallback_content::Migration),
+            Box::new(m20261017_000009_add_screen_layout::Migration),
+            Box::new(m20261017_000010_add_url_content_type::Migration),
+            Box::new(m20261017_000011_add_text_content::Migration),
+            Box::new(m20261017_000012_add_content_renditions::Migration),
+            Box::new(m20261017_000013_add_content_video_codec::Migration),
         ]
     }
 }
@@ -0,0 +1,50 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SlideGroup::Table)
+                    .add_column(boolean(SlideGroup::Takeover).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Priority used to make a slide group the only one shown, so keep that behaviour for
+        // slide groups which are currently pinned. Their priority is reset, as it now makes them
+        // show up more often in the rotation, which they would keep doing once unpinned.
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(SlideGroup::Table)
+                    .value(SlideGroup::Takeover, true)
+                    .value(SlideGroup::Priority, 0)
+                    .and_where(Expr::col(SlideGroup::Priority).gt(0))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SlideGroup::Table)
+                    .drop_column(SlideGroup::Takeover)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SlideGroup {
+    Table,
+    Priority,
+    Takeover,
+}
@@ -0,0 +1,54 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports a single change per `ALTER TABLE` statement
+        for column in [
+            integer_null(SlideGroup::RecurrenceWeekdays),
+            time_null(SlideGroup::RecurrenceStartTime),
+            time_null(SlideGroup::RecurrenceEndTime),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(SlideGroup::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            SlideGroup::RecurrenceWeekdays,
+            SlideGroup::RecurrenceStartTime,
+            SlideGroup::RecurrenceEndTime,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(SlideGroup::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SlideGroup {
+    Table,
+    RecurrenceWeekdays,
+    RecurrenceStartTime,
+    RecurrenceEndTime,
+}
@@ -0,0 +1,66 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SlideGroupScreen::Table)
+                    .if_not_exists()
+                    .col(integer(SlideGroupScreen::SlideGroup))
+                    .col(integer(SlideGroupScreen::Screen))
+                    .primary_key(
+                        Index::create()
+                            .col(SlideGroupScreen::SlideGroup)
+                            .col(SlideGroupScreen::Screen),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-slide_group_screen-slide_group")
+                            .from(SlideGroupScreen::Table, SlideGroupScreen::SlideGroup)
+                            .to(SlideGroup::Table, SlideGroup::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-slide_group_screen-screen")
+                            .from(SlideGroupScreen::Table, SlideGroupScreen::Screen)
+                            .to(Screen::Table, Screen::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .take(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SlideGroupScreen::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SlideGroupScreen {
+    Table,
+    SlideGroup,
+    Screen,
+}
+
+#[derive(DeriveIden)]
+enum SlideGroup {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Screen {
+    Table,
+    Id,
+}
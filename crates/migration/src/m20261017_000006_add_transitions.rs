@@ -0,0 +1,122 @@
+use extension::postgres::Type;
+use sea_orm::{DatabaseBackend, EnumIter, Iterable};
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let is_postgres = matches!(manager.get_database_backend(), DatabaseBackend::Postgres);
+        if is_postgres {
+            manager
+                .create_type(
+                    Type::create()
+                        .as_enum(TransitionType)
+                        .values(TransitionTypeVariants::iter())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // SQLite only supports a single change per `ALTER TABLE` statement
+        for column in [
+            enumeration_null(
+                SlideGroup::Transition,
+                TransitionType,
+                TransitionTypeVariants::iter(),
+            ),
+            integer_null(SlideGroup::TransitionDurationMs),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(SlideGroup::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        for column in [
+            enumeration(
+                Screen::Transition,
+                TransitionType,
+                TransitionTypeVariants::iter(),
+            )
+            .default("cut")
+            .to_owned(),
+            integer(Screen::TransitionDurationMs).default(0).to_owned(),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Screen::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [SlideGroup::Transition, SlideGroup::TransitionDurationMs] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(SlideGroup::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        for column in [Screen::Transition, Screen::TransitionDurationMs] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Screen::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        let is_postgres = matches!(manager.get_database_backend(), DatabaseBackend::Postgres);
+        if is_postgres {
+            manager
+                .drop_type(Type::drop().name(TransitionType).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SlideGroup {
+    Table,
+    Transition,
+    TransitionDurationMs,
+}
+
+#[derive(DeriveIden)]
+enum Screen {
+    Table,
+    Transition,
+    TransitionDurationMs,
+}
+
+#[derive(DeriveIden)]
+struct TransitionType;
+
+#[derive(DeriveIden, EnumIter)]
+enum TransitionTypeVariants {
+    Cut,
+    Crossfade,
+    SlideLeft,
+    SlideUp,
+}
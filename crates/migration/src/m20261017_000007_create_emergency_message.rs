@@ -0,0 +1,43 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EmergencyMessage::Table)
+                    .if_not_exists()
+                    .col(pk_auto(EmergencyMessage::Id))
+                    .col(text(EmergencyMessage::Message))
+                    .col(string_null(EmergencyMessage::FilePath))
+                    .col(string(EmergencyMessage::CreatedBy))
+                    .col(timestamp(EmergencyMessage::CreatedAt))
+                    .col(timestamp_null(EmergencyMessage::ExpiresAt))
+                    .col(timestamp_null(EmergencyMessage::ClearedAt))
+                    .take(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EmergencyMessage::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EmergencyMessage {
+    Table,
+    Id,
+    Message,
+    FilePath,
+    CreatedBy,
+    CreatedAt,
+    ExpiresAt,
+    ClearedAt,
+}
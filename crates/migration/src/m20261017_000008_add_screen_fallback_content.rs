@@ -0,0 +1,70 @@
+use sea_orm::DatabaseBackend;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Screen::Table)
+                    .add_column(integer_null(Screen::FallbackContent))
+                    .to_owned(),
+            )
+            .await?;
+
+        // SQLite can't add foreign keys to existing tables
+        if matches!(manager.get_database_backend(), DatabaseBackend::Postgres) {
+            manager
+                .create_foreign_key(
+                    ForeignKey::create()
+                        .name("fk-screen-fallback_content")
+                        .from(Screen::Table, Screen::FallbackContent)
+                        .to(Content::Table, Content::Id)
+                        .on_delete(ForeignKeyAction::SetNull)
+                        .on_update(ForeignKeyAction::Cascade)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if matches!(manager.get_database_backend(), DatabaseBackend::Postgres) {
+            manager
+                .drop_foreign_key(
+                    ForeignKey::drop()
+                        .name("fk-screen-fallback_content")
+                        .table(Screen::Table)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Screen::Table)
+                    .drop_column(Screen::FallbackContent)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Screen {
+    Table,
+    FallbackContent,
+}
+
+#[derive(DeriveIden)]
+enum Content {
+    Table,
+    Id,
+}
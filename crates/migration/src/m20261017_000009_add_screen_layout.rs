@@ -0,0 +1,50 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports a single change per `ALTER TABLE` statement
+        for column in [
+            integer(Screen::Width).default(1920).to_owned(),
+            integer(Screen::Height).default(1080).to_owned(),
+            integer(Screen::BezelGap).default(0).to_owned(),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Screen::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Screen::Width, Screen::Height, Screen::BezelGap] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Screen::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Screen {
+    Table,
+    Width,
+    Height,
+    BezelGap,
+}
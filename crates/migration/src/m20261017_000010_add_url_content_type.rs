@@ -0,0 +1,40 @@
+use extension::postgres::Type;
+use sea_orm::DatabaseBackend;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // enums are stored as text on other databases, so there is nothing to change there
+        let is_postgres = matches!(manager.get_database_backend(), DatabaseBackend::Postgres);
+        if is_postgres {
+            manager
+                .alter_type(
+                    Type::alter()
+                        .name(ContentType)
+                        .add_value(ContentTypeVariants::Url)
+                        .if_not_exists(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // NOTE: values can't be removed from enums in postgres, so the value is kept, which is
+        // harmless since `up` only adds it if it doesn't exist
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+struct ContentType;
+
+#[derive(DeriveIden)]
+enum ContentTypeVariants {
+    Url,
+}
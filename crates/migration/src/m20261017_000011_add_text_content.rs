@@ -0,0 +1,79 @@
+use extension::postgres::Type;
+use sea_orm::DatabaseBackend;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // enums are stored as text on other databases, so there is nothing to change there
+        let is_postgres = matches!(manager.get_database_backend(), DatabaseBackend::Postgres);
+        if is_postgres {
+            manager
+                .alter_type(
+                    Type::alter()
+                        .name(ContentType)
+                        .add_value(ContentTypeVariants::Text)
+                        .if_not_exists(),
+                )
+                .await?;
+        }
+
+        // SQLite only supports a single change per `ALTER TABLE` statement
+        for column in [
+            text_null(Content::Markdown),
+            string_null(Content::BackgroundColor),
+            string_null(Content::TextColor),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Content::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Content::Markdown,
+            Content::BackgroundColor,
+            Content::TextColor,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Content::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // NOTE: values can't be removed from enums in postgres, so the value is kept, which is
+        // harmless since `up` only adds it if it doesn't exist
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Content {
+    Table,
+    Markdown,
+    BackgroundColor,
+    TextColor,
+}
+
+#[derive(DeriveIden)]
+struct ContentType;
+
+#[derive(DeriveIden)]
+enum ContentTypeVariants {
+    Text,
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports a single change per `ALTER TABLE` statement
+        for column in [Content::RenditionPath, Content::ThumbnailPath] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Content::Table)
+                        .add_column(string_null(column))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Content::RenditionPath, Content::ThumbnailPath] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Content::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Content {
+    Table,
+    RenditionPath,
+    ThumbnailPath,
+}